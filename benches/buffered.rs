@@ -44,7 +44,7 @@ mod benches {
             circle(width, 0, 35),
         ] {
             let image_buffer = byte_image_from_function(width, height, sdf);
-            let binary = binary_image::of_byte_slice(&image_buffer, width as u16, height as u16);
+            let binary = binary_image::of_byte_slice(&image_buffer, width as u32, height as u32);
 
             bencher.iter(|| SignedDistanceField::<D>::compute(&binary));
         }
@@ -55,7 +55,7 @@ mod benches {
         let height = 4096;
 
         let image_buffer = byte_image_from_function(width, height, circle(width/2, height/2, 6));
-        let binary = binary_image::of_byte_slice(&image_buffer, width as u16, height as u16);
+        let binary = binary_image::of_byte_slice(&image_buffer, width as u32, height as u32);
         bencher.iter(|| SignedDistanceField::<D>::compute(&binary));
     }
 
@@ -0,0 +1,19 @@
+fn main(){
+    use signed_distance_field::prelude::*;
+
+    // usage: cargo run --release --example glyph_atlas --features font,piston_image -- font.ttf atlas
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    let (font_path, output) = match arguments.as_slice() {
+        [font_path, output] => (font_path, output),
+        _ => panic!("expected a font file and an output file name without extension"),
+    };
+
+    // read the font file and generate the distance fields of all printable ascii characters
+    let font = std::fs::read(font_path).unwrap();
+    let characters = (' ' ..= '~').collect::<Vec<char>>();
+    let atlas = GlyphAtlas::generate(&font, characters, GlyphAtlasSettings::default()).unwrap();
+
+    // save the atlas as png and the glyph metrics as json
+    atlas.to_gray_u8_image().save(format!("{}.png", output)).unwrap();
+    std::fs::write(format!("{}.json", output), atlas.metrics_to_json()).unwrap();
+}
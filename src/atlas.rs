@@ -0,0 +1,393 @@
+//! Packs many distance fields into a single texture.
+
+use crate::distance_field::{ SignedDistanceField, NormalizedDistanceField, DistanceStorage, TargetStorage, Rectangle };
+use crate::error::{ Error, Result };
+
+/// Selects how the rectangles are arranged in the atlas.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PackingAlgorithm {
+    /// Places the rectangles in rows from left to right, with the tallest rectangles in the first rows.
+    /// Fast, and efficient for rectangles of similar height, like glyphs.
+    Shelf,
+
+    /// Places each rectangle at the lowest position in any of the free areas of the atlas.
+    /// Slower, but efficient for rectangles of very different sizes.
+    MaxRects,
+}
+
+/// Arranges rectangles in an atlas with a fixed width, such that they do not overlap.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AtlasPacker {
+    /// The width of the atlas in pixels, unless a single rectangle is wider.
+    /// The height grows with the number of rectangles.
+    pub width: u32,
+
+    /// The number of empty pixels between two rectangles and between the rectangles and the border.
+    pub padding: u32,
+
+    pub algorithm: PackingAlgorithm,
+}
+
+/// Multiple distance fields in a single texture, produced by `AtlasPacker`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PackedAtlas {
+    pub width: u32,
+    pub height: u32,
+
+    /// A row-major image vector with one normalized distance in the range `[0, 1]` per pixel.
+    /// Pixels which are not covered by any distance field are one, the largest outside distance.
+    pub distances: Vec<f32>,
+
+    /// The rectangle of each distance field in the atlas, in the order of the packed fields.
+    pub placements: Vec<Rectangle>,
+}
+
+
+impl Default for AtlasPacker {
+    fn default() -> Self {
+        AtlasPacker { width: 1024, padding: 1, algorithm: PackingAlgorithm::MaxRects }
+    }
+}
+
+impl AtlasPacker {
+    pub fn new(width: u32, padding: u32, algorithm: PackingAlgorithm) -> Self {
+        AtlasPacker { width, padding, algorithm }
+    }
+
+    /// Arranges rectangles of the specified sizes without overlap.
+    /// Returns the placement of each rectangle, in the original order, as well as the width and height of the atlas.
+    /// Rectangles without pixels are placed at the origin.
+    pub fn pack(&self, sizes: &[(u32, u32)]) -> (Vec<Rectangle>, u32, u32) {
+        let widest = sizes.iter().map(|&(width, _)| width + 2 * self.padding).max().unwrap_or(0);
+        let width = self.width.max(widest);
+
+        // every rectangle is grown by the padding to the right and bottom,
+        // and the atlas starts after the padding at the left and top
+        let padded_sizes: Vec<(u32, u32)> = sizes.iter()
+            .map(|&(width, height)| (width + self.padding, height + self.padding))
+            .collect();
+
+        let mut order: Vec<usize> = (0 .. sizes.len())
+            .filter(|&index| sizes[index].0 > 0 && sizes[index].1 > 0)
+            .collect();
+
+        order.sort_by_key(|&index| std::cmp::Reverse((sizes[index].1, sizes[index].0)));
+
+        let positions = match self.algorithm {
+            PackingAlgorithm::Shelf => pack_shelves(&padded_sizes, &order, width.saturating_sub(self.padding)),
+            PackingAlgorithm::MaxRects => pack_max_rects(&padded_sizes, &order, width.saturating_sub(self.padding)),
+        };
+
+        let placements: Vec<Rectangle> = sizes.iter().zip(positions)
+            .map(|(&(width, height), position)| match position {
+                Some((x, y)) => Rectangle::new(x + self.padding, y + self.padding, width, height),
+                None => Rectangle::new(0, 0, width, height),
+            })
+            .collect();
+
+        let height = placements.iter()
+            .filter(|placement| !placement.is_empty())
+            .map(|placement| placement.y + placement.height + self.padding)
+            .max().unwrap_or(0);
+
+        (placements, width, height)
+    }
+
+    /// Copies the normalized distance fields into a single atlas.
+    pub fn pack_normalized<D, T>(&self, distance_fields: &[NormalizedDistanceField<D, T>]) -> PackedAtlas
+        where D: DistanceStorage, T: TargetStorage
+    {
+        self.compose(
+            distance_fields.iter().map(|field| (field.width, field.height)),
+            |index, pixel| distance_fields[index].distances.get(pixel)
+        )
+    }
+
+    /// Normalizes the distance fields such that `min` distances are zero and `max` distances are one,
+    /// like `NormalizedDistanceField::normalize_clamped`, and copies them into a single atlas.
+    /// Infinite distances, like the saturated distances of quantized storages, are clamped as well.
+    /// Returns `None` if any of the distance fields does not contain any shapes.
+    pub fn pack_signed<D, T>(&self, distance_fields: &[SignedDistanceField<D, T>], min: f32, max: f32) -> Option<PackedAtlas>
+        where D: DistanceStorage, T: TargetStorage
+    {
+        self.try_pack_signed(distance_fields, min, max).ok()
+    }
+
+    /// Normalizes and copies the distance fields into a single atlas, like `pack_signed`.
+    /// Returns an error if any of the distance fields does not contain any shapes,
+    /// or if `min` and `max` are equal.
+    pub fn try_pack_signed<D, T>(&self, distance_fields: &[SignedDistanceField<D, T>], min: f32, max: f32) -> Result<PackedAtlas>
+        where D: DistanceStorage, T: TargetStorage
+    {
+        if min == max { return Err(Error::ZeroSizeRange); }
+
+        let has_empty_field = distance_fields.iter().any(|field| {
+            let pixel_count = field.width as usize * field.height as usize;
+            pixel_count != 0 && (0 .. pixel_count).all(|index| field.distances.get(index).is_infinite())
+        });
+
+        if has_empty_field { return Err(Error::NoShapeFound); }
+
+        Ok(self.compose(
+            distance_fields.iter().map(|field| (field.width, field.height)),
+            |index, pixel| (distance_fields[index].distances.get(pixel).clamp(min, max) - min) / (max - min)
+        ))
+    }
+
+    /// Packs rectangles of the specified sizes and fills them with the normalized distance
+    /// of the specified field and pixel index.
+    fn compose(&self, sizes: impl Iterator<Item = (u32, u32)>, distance: impl Fn(usize, usize) -> f32) -> PackedAtlas {
+        let sizes: Vec<(u32, u32)> = sizes.collect();
+        let (placements, width, height) = self.pack(&sizes);
+        let mut distances = vec![ 1.0; width as usize * height as usize ];
+
+        for (index, placement) in placements.iter().enumerate() {
+            for y in 0 .. placement.height as usize {
+                for x in 0 .. placement.width as usize {
+                    let atlas_index = (placement.y as usize + y) * width as usize + placement.x as usize + x;
+                    distances[atlas_index] = distance(index, y * placement.width as usize + x);
+                }
+            }
+        }
+
+        PackedAtlas { width, height, distances, placements }
+    }
+}
+
+impl PackedAtlas {
+    /// Convert the normalized distances to an `u8` image with the range fully utilized.
+    pub fn to_u8(&self) -> Vec<u8> {
+        self.distances.iter()
+            .map(|&distance| (distance.clamp(0.0, 1.0) * u8::MAX as f32) as u8)
+            .collect()
+    }
+
+    /// Convert the normalized distances to an `u16` image with the range fully utilized.
+    pub fn to_u16(&self) -> Vec<u16> {
+        self.distances.iter()
+            .map(|&distance| (distance.clamp(0.0, 1.0) * u16::MAX as f32) as u16)
+            .collect()
+    }
+
+    /// Convert the normalized distances to an `u8` gray piston image with the range fully utilized.
+    #[cfg(feature = "piston_image")]
+    pub fn to_gray_u8_image(&self) -> image::GrayImage {
+        image::GrayImage::from_raw(self.width, self.height, self.to_u8())
+            .expect("incorrect vector length")
+    }
+}
+
+/// Places the rectangles in the specified order in rows from left to right,
+/// starting a new row when a rectangle does not fit.
+fn pack_shelves(sizes: &[(u32, u32)], order: &[usize], width: u32) -> Vec<Option<(u32, u32)>> {
+    let mut positions = vec![ None; sizes.len() ];
+    let (mut x, mut y, mut row_height) = (0, 0, 0);
+
+    for &index in order {
+        let (rectangle_width, rectangle_height) = sizes[index];
+
+        if x + rectangle_width > width {
+            x = 0;
+            y += row_height;
+            row_height = 0;
+        }
+
+        positions[index] = Some((x, y));
+        x += rectangle_width;
+        row_height = row_height.max(rectangle_height);
+    }
+
+    positions
+}
+
+/// Places the rectangles in the specified order, each at the lowest position
+/// in any of the maximal free rectangles, which are updated after each placement.
+fn pack_max_rects(sizes: &[(u32, u32)], order: &[usize], width: u32) -> Vec<Option<(u32, u32)>> {
+    let total_height = order.iter().map(|&index| sizes[index].1).sum();
+    let mut free = vec![ Rectangle::new(0, 0, width, total_height) ];
+    let mut positions = vec![ None; sizes.len() ];
+
+    for &index in order {
+        let (rectangle_width, rectangle_height) = sizes[index];
+
+        let best = free.iter()
+            .filter(|area| area.width >= rectangle_width && area.height >= rectangle_height)
+            .min_by_key(|area| (area.y + rectangle_height, area.x))
+            .copied()
+            .expect("the free area is as high as all rectangles together");
+
+        let placed = Rectangle::new(best.x, best.y, rectangle_width, rectangle_height);
+        positions[index] = Some((placed.x, placed.y));
+
+        // split all free areas which overlap the placed rectangle into the parts around it
+        let mut split = Vec::with_capacity(free.len() + 4);
+        for area in free {
+            if !overlaps(area, placed) {
+                split.push(area);
+                continue;
+            }
+
+            let (right, bottom) = (area.x + area.width, area.y + area.height);
+            let (placed_right, placed_bottom) = (placed.x + placed.width, placed.y + placed.height);
+
+            if placed.x > area.x { split.push(Rectangle::new(area.x, area.y, placed.x - area.x, area.height)); }
+            if placed_right < right { split.push(Rectangle::new(placed_right, area.y, right - placed_right, area.height)); }
+            if placed.y > area.y { split.push(Rectangle::new(area.x, area.y, area.width, placed.y - area.y)); }
+            if placed_bottom < bottom { split.push(Rectangle::new(area.x, placed_bottom, area.width, bottom - placed_bottom)); }
+        }
+
+        // remove free areas which are contained in other free areas
+        free = split.iter().enumerate()
+            .filter(|&(index, &area)| !split.iter().enumerate().any(|(other_index, &other)| {
+                other_index != index && contains(other, area) && (other != area || other_index < index)
+            }))
+            .map(|(_, &area)| area)
+            .collect();
+    }
+
+    positions
+}
+
+/// Whether the two rectangles share any pixel.
+#[inline]
+fn overlaps(first: Rectangle, second: Rectangle) -> bool {
+    first.x < second.x + second.width && second.x < first.x + first.width
+        && first.y < second.y + second.height && second.y < first.y + first.height
+}
+
+/// Whether the outer rectangle contains all pixels of the inner rectangle.
+#[inline]
+fn contains(outer: Rectangle, inner: Rectangle) -> bool {
+    inner.x >= outer.x && inner.y >= outer.y
+        && inner.x + inner.width <= outer.x + outer.width
+        && inner.y + inner.height <= outer.y + outer.height
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use super::overlaps;
+
+    fn assert_valid_packing(sizes: &[(u32, u32)], packer: AtlasPacker) -> (u32, u32) {
+        let (placements, width, height) = packer.pack(sizes);
+        assert_eq!(placements.len(), sizes.len());
+
+        for (index, placement) in placements.iter().enumerate() {
+            assert_eq!((placement.width, placement.height), sizes[index]);
+            if placement.is_empty() { continue; }
+
+            assert!(placement.x >= packer.padding && placement.y >= packer.padding);
+            assert!(placement.x + placement.width + packer.padding <= width);
+            assert!(placement.y + placement.height + packer.padding <= height);
+
+            for other in placements.iter().skip(index + 1).filter(|other| !other.is_empty()) {
+                let padded = Rectangle::new(
+                    placement.x, placement.y,
+                    placement.width + packer.padding, placement.height + packer.padding
+                );
+
+                assert!(!overlaps(padded, *other) && !overlaps(*other, padded), "{:?} and {:?}", placement, other);
+            }
+        }
+
+        (width, height)
+    }
+
+    fn various_sizes() -> Vec<(u32, u32)> {
+        (0 .. 60_u32).map(|index| (3 + index * 7 % 23, 2 + index * 11 % 31)).chain(vec![ (0, 0), (64, 1) ]).collect()
+    }
+
+    #[test]
+    pub fn shelf_packing_does_not_overlap(){
+        assert_valid_packing(&various_sizes(), AtlasPacker::new(64, 0, PackingAlgorithm::Shelf));
+        assert_valid_packing(&various_sizes(), AtlasPacker::new(100, 2, PackingAlgorithm::Shelf));
+    }
+
+    #[test]
+    pub fn max_rects_packing_does_not_overlap(){
+        assert_valid_packing(&various_sizes(), AtlasPacker::new(64, 0, PackingAlgorithm::MaxRects));
+        let (width, _) = assert_valid_packing(&various_sizes(), AtlasPacker::new(100, 2, PackingAlgorithm::MaxRects));
+        assert_eq!(width, 100);
+    }
+
+    #[test]
+    pub fn max_rects_packs_tightly(){
+        let sizes = vec![ (16, 16); 16 ];
+        let (width, height) = assert_valid_packing(&sizes, AtlasPacker::new(64, 0, PackingAlgorithm::MaxRects));
+        assert_eq!((width, height), (64, 64));
+
+        let mixed = vec![ (32, 32), (16, 16), (16, 16), (32, 16), (16, 16), (16, 16), (64, 16) ];
+        let (_, height) = assert_valid_packing(&mixed, AtlasPacker::new(64, 0, PackingAlgorithm::MaxRects));
+        assert_eq!(height, 64);
+    }
+
+    #[test]
+    pub fn wide_rectangles_widen_the_atlas(){
+        let (width, _) = assert_valid_packing(&[ (10, 10), (80, 4) ], AtlasPacker::new(32, 1, PackingAlgorithm::Shelf));
+        assert_eq!(width, 82);
+    }
+
+    #[test]
+    pub fn pack_distance_fields(){
+        let fields: Vec<SignedDistanceField<F32DistanceStorage>> = (1 .. 5)
+            .map(|size| {
+                let image = binary_image::of_function(size * 8, size * 6, |x, y| x > 2 && y > 2);
+                SignedDistanceField::compute_exact(&image)
+            })
+            .collect();
+
+        let atlas = AtlasPacker::new(64, 1, PackingAlgorithm::MaxRects).pack_signed(&fields, -4.0, 4.0).unwrap();
+        let bytes = atlas.to_u8();
+        assert_eq!(bytes.len(), atlas.width as usize * atlas.height as usize);
+        assert_eq!(atlas.to_u16().len(), bytes.len());
+
+        for (field, placement) in fields.iter().zip(&atlas.placements) {
+            for &(x, y) in &[ (0, 0), (3, 3), (field.width - 1, field.height - 1) ] {
+                let expected = (field.get_distance(x, y).clamp(-4.0, 4.0) + 4.0) / 8.0;
+                let index = (placement.y + y) * atlas.width + placement.x + x;
+                assert_eq!(atlas.distances[index as usize], expected);
+            }
+        }
+
+        // the padding is outside of all shapes
+        assert_eq!(bytes[0], 255);
+
+        let normalized: Vec<_> = fields.into_iter()
+            .map(|field| field.normalize_clamped_distances(-4.0, 4.0).unwrap())
+            .collect();
+
+        let normalized_atlas = AtlasPacker::new(64, 1, PackingAlgorithm::MaxRects).pack_normalized(&normalized);
+        assert_eq!(normalized_atlas, atlas);
+    }
+
+    #[test]
+    pub fn empty_fields_cannot_be_packed(){
+        let image = binary_image::of_function(4, 4, |_, _| false);
+        let fields = vec![ SignedDistanceField::<F32DistanceStorage>::compute_exact(&image) ];
+
+        assert_eq!(AtlasPacker::default().try_pack_signed(&fields, -4.0, 4.0).unwrap_err(), Error::NoShapeFound);
+        assert_eq!(AtlasPacker::default().try_pack_signed(&fields, 4.0, 4.0).unwrap_err(), Error::ZeroSizeRange);
+    }
+
+    #[test]
+    pub fn saturated_fields_are_clamped(){
+        let image = binary_image::of_function(40, 40, |x, y| x > 30 && y > 30);
+        let fields = vec![ SignedDistanceField::<U8DistanceStorage<4>>::compute_exact(&image) ];
+        assert_eq!(fields[0].get_distance(0, 0), f32::INFINITY);
+
+        let atlas = AtlasPacker::new(64, 0, PackingAlgorithm::Shelf).try_pack_signed(&fields, -4.0, 4.0).unwrap();
+        assert_eq!(atlas.distances[0], 1.0);
+        assert_eq!(atlas.distances[35 * atlas.width as usize + 35], 0.0);
+    }
+
+    #[test]
+    pub fn no_sizes_pack_into_an_empty_atlas(){
+        let (placements, width, height) = AtlasPacker::new(0, 2, PackingAlgorithm::Shelf).pack(&[]);
+        assert!(placements.is_empty());
+        assert_eq!((width, height), (0, 0));
+
+        let (_, width, height) = AtlasPacker::new(0, 2, PackingAlgorithm::MaxRects).pack(&[]);
+        assert_eq!((width, height), (0, 0));
+    }
+}
@@ -0,0 +1,312 @@
+//! Converts images to signed distance fields.
+//! Run `sdf --help` for a list of all options.
+
+use std::error::Error;
+use std::path::{ Path, PathBuf };
+use signed_distance_field::prelude::*;
+
+const USAGE: &str = "\
+Converts images to signed distance fields.
+
+USAGE:
+    sdf [OPTIONS] <INPUT> <OUTPUT>
+
+    If INPUT is a directory, every image in that directory is converted
+    and written to the OUTPUT directory, which is created if necessary.
+
+OPTIONS:
+    -t, --threshold <0-255>     Pixels brighter than this are inside the shape [default: 127]
+    -i, --invert                Pixels darker than the threshold are inside the shape instead
+    -c, --clamp <MIN,MAX>       The distance range in pixels which is mapped to black and white,
+                                a single value `D` meaning `-D,D` [default: -10,10]
+    -p, --precision <f16|f32>   The precision of the distances while computing [default: f32]
+    -b, --bits <8|16>           The bit depth of the output image [default: 8]
+    -f, --format <EXTENSION>    The output image format, for example `png` or `bmp`
+                                [default: the extension of OUTPUT, or `png` for directories]
+    -h, --help                  Print this message
+";
+
+/// All settings of a conversion, as specified on the command line.
+#[derive(Debug, Clone, PartialEq)]
+struct Options {
+    input: PathBuf,
+    output: PathBuf,
+    threshold: u8,
+    invert: bool,
+    clamp: (f32, f32),
+    precision: Precision,
+    bits: Bits,
+    format: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Precision { F16, F32 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bits { U8, U16 }
+
+
+fn main(){
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+
+    if arguments.is_empty() || arguments.iter().any(|argument| argument == "-h" || argument == "--help") {
+        print!("{}", USAGE);
+        return;
+    }
+
+    let options = match parse_options(&arguments) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(error) = run(&options) {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+fn parse_options(arguments: &[String]) -> Result<Options, String> {
+    let mut paths = Vec::new();
+    let mut threshold = 127;
+    let mut invert = false;
+    let mut clamp = (-10.0, 10.0);
+    let mut precision = Precision::F32;
+    let mut bits = Bits::U8;
+    let mut format = None;
+
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        let mut value = || arguments.next()
+            .ok_or_else(|| format!("missing value for `{}`", argument));
+
+        match argument.as_str() {
+            "-t" | "--threshold" => {
+                let value = value()?;
+                threshold = value.parse().map_err(|_| format!("invalid threshold `{}`, expected 0-255", value))?;
+            },
+
+            "-i" | "--invert" => invert = true,
+
+            "-c" | "--clamp" => {
+                let value = value()?;
+                clamp = parse_clamp(value).ok_or_else(|| format!("invalid clamp range `{}`, expected `MIN,MAX`", value))?;
+            },
+
+            "-p" | "--precision" => precision = match value()?.as_str() {
+                "f16" => Precision::F16,
+                "f32" => Precision::F32,
+                other => return Err(format!("invalid precision `{}`, expected `f16` or `f32`", other)),
+            },
+
+            "-b" | "--bits" => bits = match value()?.as_str() {
+                "8" => Bits::U8,
+                "16" => Bits::U16,
+                other => return Err(format!("invalid bit depth `{}`, expected `8` or `16`", other)),
+            },
+
+            "-f" | "--format" => format = Some(value()?.trim_start_matches('.').to_ascii_lowercase()),
+
+            other if other.starts_with('-') && other.len() > 1 => {
+                return Err(format!("unknown option `{}`", other))
+            },
+
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    if paths.len() != 2 {
+        return Err(format!("expected an input and an output path, but found {} paths", paths.len()));
+    }
+
+    let output = paths.pop().unwrap();
+    let input = paths.pop().unwrap();
+
+    Ok(Options { input, output, threshold, invert, clamp, precision, bits, format })
+}
+
+/// Parses either `MIN,MAX` or a single distance `D`, meaning `-D,D`.
+fn parse_clamp(value: &str) -> Option<(f32, f32)> {
+    let (min, max) = match value.find(',') {
+        Some(comma) => (value[.. comma].trim().parse().ok()?, value[comma + 1 ..].trim().parse().ok()?),
+        None => {
+            let distance: f32 = value.trim().parse().ok()?;
+            (- distance.abs(), distance.abs())
+        }
+    };
+
+    if min < max { Some((min, max)) } else { None }
+}
+
+/// Converts a single image or all images of a directory.
+fn run(options: &Options) -> Result<(), Box<dyn Error>> {
+    if !options.input.is_dir() {
+        let output = match &options.format {
+            Some(format) => options.output.with_extension(format),
+            None => options.output.clone(),
+        };
+
+        return convert(&options.input, &output, options);
+    }
+
+    std::fs::create_dir_all(&options.output)?;
+
+    let mut inputs = std::fs::read_dir(&options.input)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+
+    inputs.retain(|path| path.is_file());
+    inputs.sort();
+
+    let format = options.format.as_deref().unwrap_or("png");
+    let mut failures = 0;
+
+    for input in &inputs {
+        let name = input.file_stem().ok_or("invalid file name")?;
+        let output = options.output.join(name).with_extension(format);
+
+        match convert(input, &output, options) {
+            Ok(()) => println!("{} -> {}", input.display(), output.display()),
+            Err(error) => {
+                eprintln!("skipping {}: {}", input.display(), error);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures == 0 { Ok(()) }
+    else { Err(format!("{} of {} files could not be converted", failures, inputs.len()).into()) }
+}
+
+/// Converts the image at the input path and saves the distance field to the output path.
+fn convert(input: &Path, output: &Path, options: &Options) -> Result<(), Box<dyn Error>> {
+    let is_png = output.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+    if options.bits == Bits::U16 && !is_png {
+        return Err("16 bit output is only supported for png images".into());
+    }
+
+    let gray_image = image::open(input)?.to_luma();
+    let (width, height) = (gray_image.width(), gray_image.height());
+
+    let binary_image = binary_image::of_function(width, height, |x, y| {
+        is_inside(gray_image.get_pixel(x, y).data[0], options)
+    });
+
+    let bytes = match options.precision {
+        Precision::F16 => distance_field_bytes::<F16DistanceStorage>(&binary_image, options)?,
+        Precision::F32 => distance_field_bytes::<F32DistanceStorage>(&binary_image, options)?,
+    };
+
+    let color = match options.bits {
+        Bits::U8 => image::ColorType::Gray(8),
+        Bits::U16 => image::ColorType::Gray(16),
+    };
+
+    image::save_buffer(output, &bytes, width, height, color)?;
+    Ok(())
+}
+
+/// Whether a pixel with the specified brightness is inside the shape,
+/// being brighter than the threshold, or darker if the colors are inverted.
+fn is_inside(brightness: u8, options: &Options) -> bool {
+    if options.invert { brightness < options.threshold }
+    else { brightness > options.threshold }
+}
+
+/// Computes the distance field and encodes it with the configured bit depth.
+/// Sixteen bit samples are stored in big endian byte order.
+fn distance_field_bytes<D: DistanceStorage>(image: &impl BinaryImage, options: &Options) -> Result<Vec<u8>, Box<dyn Error>> {
+    let (min, max) = options.clamp;
+    let normalized = SignedDistanceField::<D>::try_compute(image)?
+        .try_normalize_clamped_distances(min, max)?;
+
+    Ok(match options.bits {
+        Bits::U8 => normalized.to_u8(),
+        Bits::U16 => normalized.to_u16().iter().flat_map(|sample| sample.to_be_bytes().to_vec()).collect(),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(arguments: &str) -> Vec<String> {
+        arguments.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_default_options(){
+        let options = parse_options(&arguments("in.png out.png")).unwrap();
+
+        assert_eq!(options, Options {
+            input: PathBuf::from("in.png"),
+            output: PathBuf::from("out.png"),
+            threshold: 127,
+            invert: false,
+            clamp: (-10.0, 10.0),
+            precision: Precision::F32,
+            bits: Bits::U8,
+            format: None,
+        });
+    }
+
+    #[test]
+    fn parse_all_options(){
+        let options = parse_options(&arguments(
+            "-t 80 --invert in out --clamp -4,12 -p f16 --bits 16 -f .PNG"
+        )).unwrap();
+
+        assert_eq!(options, Options {
+            input: PathBuf::from("in"),
+            output: PathBuf::from("out"),
+            threshold: 80,
+            invert: true,
+            clamp: (-4.0, 12.0),
+            precision: Precision::F16,
+            bits: Bits::U16,
+            format: Some(String::from("png")),
+        });
+    }
+
+    #[test]
+    fn parse_invalid_options(){
+        assert!(parse_options(&arguments("in.png")).is_err());
+        assert!(parse_options(&arguments("in.png out.png extra.png")).is_err());
+        assert!(parse_options(&arguments("in.png out.png --threshold")).is_err());
+        assert!(parse_options(&arguments("in.png out.png --threshold 256")).is_err());
+        assert!(parse_options(&arguments("in.png out.png --clamp 3,1")).is_err());
+        assert!(parse_options(&arguments("in.png out.png --precision f64")).is_err());
+        assert!(parse_options(&arguments("in.png out.png --bits 32")).is_err());
+        assert!(parse_options(&arguments("in.png out.png --unknown")).is_err());
+    }
+
+    #[test]
+    fn parse_clamp_ranges(){
+        assert_eq!(parse_clamp("5"), Some((-5.0, 5.0)));
+        assert_eq!(parse_clamp("-5"), Some((-5.0, 5.0)));
+        assert_eq!(parse_clamp("-2,8"), Some((-2.0, 8.0)));
+        assert_eq!(parse_clamp(" -2 , 8 "), Some((-2.0, 8.0)));
+
+        assert_eq!(parse_clamp("0"), None);
+        assert_eq!(parse_clamp("3,1"), None);
+        assert_eq!(parse_clamp("2,2"), None);
+        assert_eq!(parse_clamp("a,b"), None);
+        assert_eq!(parse_clamp(""), None);
+    }
+
+    #[test]
+    fn invert_selects_pixels_darker_than_threshold(){
+        let mut options = parse_options(&arguments("in.png out.png --threshold 80")).unwrap();
+        let pixels = [10, 60, 80, 100, 200];
+
+        let inside: Vec<bool> = pixels.iter().map(|&pixel| is_inside(pixel, &options)).collect();
+        assert_eq!(inside, vec![false, false, false, true, true]);
+
+        options.invert = true;
+        let inside: Vec<bool> = pixels.iter().map(|&pixel| is_inside(pixel, &options)).collect();
+        assert_eq!(inside, vec![true, true, false, false, false]);
+    }
+}
@@ -1,25 +1,31 @@
+use crate::error::{ Error, Result };
 
 /// Represents an image with each pixel being either true or false,
 /// corresponding to inside-the-shape and outside-of-the-shape respectively.
 /// BinaryImages can be created from byte slices
 /// or piston images if the feature `piston_image` is activated.
 pub trait BinaryImage {
-    #[inline]
-    fn width(&self) -> u16;
+    fn width(&self) -> u32;
 
-    #[inline]
-    fn height(&self) -> u16;
+    fn height(&self) -> u32;
+
+    fn is_inside(&self, x: u32, y: u32) -> bool;
+}
 
-    #[inline(always)]
-    fn is_inside(&self, x: u16, y: u16) -> bool;
+/// A binary image which additionally describes how much of each pixel is covered by the shape,
+/// as found in anti-aliased images. Pixels with a coverage above one half should be inside the shape.
+/// Byte images with a custom threshold remap their coverage such that one half lies at the threshold.
+pub trait CoverageImage: BinaryImage {
+    /// The fraction of the pixel area which is inside the shape, in the range `[0, 1]`.
+    fn coverage(&self, x: u32, y: u32) -> f32;
 }
 
 /// An image which is described by a row major slice of bytes, with one byte per pixel.
 /// To determine if a byte is inside or outside,
 /// it is compared to a threshold. The default threshold is 127.
 pub struct BinaryByteSliceImage<'b> {
-    width: u16,
-    height: u16,
+    width: u32,
+    height: u32,
 
     /// A row-major image vector with one byte per pixel.
     buffer: &'b [u8],
@@ -30,51 +36,134 @@ pub struct BinaryByteSliceImage<'b> {
 }
 
 /// Create a binary image from a row major byte slice with each byte brighter than 127 being "inside-the-shape"
-pub fn of_byte_slice(buffer: &[u8], width: u16, height: u16) -> BinaryByteSliceImage {
+pub fn of_byte_slice(buffer: &[u8], width: u32, height: u32) -> BinaryByteSliceImage<'_> {
     of_byte_slice_with_threshold(buffer, width, height, 127)
 }
 
 /// Create a binary image from a row major byte slice with each byte brighter than the threshold being "inside-the-shape"
-pub fn of_byte_slice_with_threshold(buffer: &[u8], width: u16, height: u16, threshold: u8) -> BinaryByteSliceImage {
-    debug_assert_eq!(buffer.len(), width as usize * height as usize, "Buffer dimension mismatch");
+pub fn of_byte_slice_with_threshold(buffer: &[u8], width: u32, height: u32, threshold: u8) -> BinaryByteSliceImage<'_> {
+    assert_eq!(buffer.len(), width as usize * height as usize, "Buffer dimension mismatch");
     BinaryByteSliceImage { width, height, buffer, threshold }
 }
 
+/// Create a binary image from a row major byte slice with each byte brighter than 127 being "inside-the-shape".
+/// Returns an error if the buffer length does not match the dimensions or if the image has no pixels.
+pub fn try_of_byte_slice(buffer: &[u8], width: u32, height: u32) -> Result<BinaryByteSliceImage<'_>> {
+    try_of_byte_slice_with_threshold(buffer, width, height, 127)
+}
+
+/// Create a binary image from a row major byte slice with each byte brighter than the threshold being "inside-the-shape".
+/// Returns an error if the buffer length does not match the dimensions or if the image has no pixels.
+pub fn try_of_byte_slice_with_threshold(buffer: &[u8], width: u32, height: u32, threshold: u8) -> Result<BinaryByteSliceImage<'_>> {
+    let expected = width as usize * height as usize;
+    if buffer.len() != expected { return Err(Error::DimensionMismatch { expected, actual: buffer.len() }); }
+    if expected == 0 { return Err(Error::EmptyImage); }
+
+    Ok(BinaryByteSliceImage { width, height, buffer, threshold })
+}
+
 
 impl BinaryImage for BinaryByteSliceImage<'_> {
     #[inline]
-    fn width(&self) -> u16 {
+    fn width(&self) -> u32 {
         self.width
     }
 
     #[inline]
-    fn height(&self) -> u16 {
+    fn height(&self) -> u32 {
         self.height
     }
 
     #[inline]
-    fn is_inside(&self, x: u16, y: u16) -> bool {
+    fn is_inside(&self, x: u32, y: u32) -> bool {
         self.buffer[self.width as usize * y as usize + x as usize] > self.threshold
     }
 }
 
 
+impl CoverageImage for BinaryByteSliceImage<'_> {
+    #[inline]
+    fn coverage(&self, x: u32, y: u32) -> f32 {
+        threshold_coverage(self.buffer[self.width as usize * y as usize + x as usize], self.threshold)
+    }
+}
+
+/// Converts a byte to a coverage in the range `[0, 1]`, such that a coverage of one half
+/// lies between the threshold and the next brighter byte, where the pixels start being inside.
+/// Bytes darker and brighter than that are scaled linearly towards zero and one.
+/// With the default threshold of 127, this is simply the byte divided by 255.
+#[inline]
+fn threshold_coverage(byte: u8, threshold: u8) -> f32 {
+    let half = threshold as f32 + 0.5;
+    let byte = byte as f32;
+
+    if byte < half { 0.5 * byte / half }
+    else { 0.5 + 0.5 * (byte - half) / (u8::MAX as f32 - half) }
+}
+
+
+/// An image which owns a row major vector with one boolean per pixel,
+/// for example the result of a morphological operation.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BinaryBoolVecImage {
+    pub width: u32,
+    pub height: u32,
+
+    /// A row-major image vector with one boolean per pixel,
+    /// which is true for pixels inside the shape.
+    pub pixels: Vec<bool>,
+}
+
+/// Create a binary image from a row major vector of booleans, with `true` being "inside-the-shape".
+pub fn of_bool_vec(pixels: Vec<bool>, width: u32, height: u32) -> BinaryBoolVecImage {
+    assert_eq!(pixels.len(), width as usize * height as usize, "Buffer dimension mismatch");
+    BinaryBoolVecImage { width, height, pixels }
+}
+
+/// Create a binary image by evaluating the function for every pixel, with `true` being "inside-the-shape".
+pub fn of_function(width: u32, height: u32, is_inside: impl Fn(u32, u32) -> bool) -> BinaryBoolVecImage {
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| is_inside(x, y))
+        .collect();
+
+    BinaryBoolVecImage { width, height, pixels }
+}
+
+impl BinaryImage for BinaryBoolVecImage {
+    #[inline]
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    fn is_inside(&self, x: u32, y: u32) -> bool {
+        self.pixels[self.width as usize * y as usize + x as usize]
+    }
+}
+
+
 /// Create binary images from piston images.
 #[cfg(feature = "piston_image")]
 pub mod piston_image {
     use image::*;
-    use super::BinaryImage;
+    use super::{ BinaryImage, CoverageImage, threshold_coverage };
 
     /// Create a binary image from a grey-scale piston image
     /// with all pixels brighter than 127 being inside-the-shape.
-    pub fn of_gray_image(image: &GrayImage) -> GrayBinaryImage<u8, Vec<u8>> {
+    pub fn of_gray_image(image: &GrayImage) -> GrayBinaryImage<'_, u8, Vec<u8>> {
         of_gray_image_with_threshold(image, 127)
     }
 
     /// Create a binary image from a grey-scale piston image
     /// with all pixels brighter than the threshold being inside-the-shape.
     pub fn of_gray_image_with_threshold(image: &GrayImage, threshold: u8)
-        -> GrayBinaryImage<u8, Vec<u8>>
+        -> GrayBinaryImage<'_, u8, Vec<u8>>
     {
         GrayBinaryImage { image, threshold }
     }
@@ -89,18 +178,25 @@ pub mod piston_image {
     impl<'i, P, C> BinaryImage for GrayBinaryImage<'i, P, C>
         where P: 'static + Primitive, C: std::ops::Deref<Target = [P]>
     {
-        fn width(&self) -> u16 {
-            self.image.width() as u16
+        fn width(&self) -> u32 {
+            self.image.width()
         }
 
-        fn height(&self) -> u16 {
-            self.image.height() as u16
+        fn height(&self) -> u32 {
+            self.image.height()
         }
 
-        fn is_inside(&self, x: u16, y: u16) -> bool {
-            self.image.get_pixel(x as u32, y as u32).data[0] > self.threshold
+        fn is_inside(&self, x: u32, y: u32) -> bool {
+            self.image.get_pixel(x, y).data[0] > self.threshold
         }
     }
 
-}
+    impl<C> CoverageImage for GrayBinaryImage<'_, u8, C>
+        where C: std::ops::Deref<Target = [u8]>
+    {
+        fn coverage(&self, x: u32, y: u32) -> f32 {
+            threshold_coverage(self.image.get_pixel(x, y).data[0], self.threshold)
+        }
+    }
 
+}
@@ -0,0 +1,82 @@
+use crate::error::{ Error, Result };
+
+/// Represents a volume with each voxel being either true or false,
+/// corresponding to inside-the-shape and outside-of-the-shape respectively.
+/// BinaryVolumes can be created from byte slices.
+pub trait BinaryVolume {
+    fn width(&self) -> u32;
+
+    fn height(&self) -> u32;
+
+    fn depth(&self) -> u32;
+
+    fn is_inside(&self, x: u32, y: u32, z: u32) -> bool;
+}
+
+/// A volume which is described by a slice of bytes, with one byte per voxel,
+/// containing one row-major image after another.
+/// To determine if a byte is inside or outside,
+/// it is compared to a threshold. The default threshold is 127.
+pub struct BinaryByteSliceVolume<'b> {
+    width: u32,
+    height: u32,
+    depth: u32,
+
+    /// One row-major image vector after another, with one byte per voxel.
+    buffer: &'b [u8],
+
+    /// A voxel must be brighter than this value
+    /// in order to be inside the shape.
+    threshold: u8,
+}
+
+/// Create a binary volume from a byte slice with each byte brighter than 127 being "inside-the-shape"
+pub fn of_byte_slice(buffer: &[u8], width: u32, height: u32, depth: u32) -> BinaryByteSliceVolume<'_> {
+    of_byte_slice_with_threshold(buffer, width, height, depth, 127)
+}
+
+/// Create a binary volume from a byte slice with each byte brighter than the threshold being "inside-the-shape"
+pub fn of_byte_slice_with_threshold(buffer: &[u8], width: u32, height: u32, depth: u32, threshold: u8) -> BinaryByteSliceVolume<'_> {
+    assert_eq!(buffer.len(), width as usize * height as usize * depth as usize, "Buffer dimension mismatch");
+    BinaryByteSliceVolume { width, height, depth, buffer, threshold }
+}
+
+/// Create a binary volume from a byte slice with each byte brighter than 127 being "inside-the-shape".
+/// Returns an error if the buffer length does not match the dimensions or if the volume has no voxels.
+pub fn try_of_byte_slice(buffer: &[u8], width: u32, height: u32, depth: u32) -> Result<BinaryByteSliceVolume<'_>> {
+    try_of_byte_slice_with_threshold(buffer, width, height, depth, 127)
+}
+
+/// Create a binary volume from a byte slice with each byte brighter than the threshold being "inside-the-shape".
+/// Returns an error if the buffer length does not match the dimensions or if the volume has no voxels.
+pub fn try_of_byte_slice_with_threshold(buffer: &[u8], width: u32, height: u32, depth: u32, threshold: u8) -> Result<BinaryByteSliceVolume<'_>> {
+    let expected = width as usize * height as usize * depth as usize;
+    if buffer.len() != expected { return Err(Error::DimensionMismatch { expected, actual: buffer.len() }); }
+    if expected == 0 { return Err(Error::EmptyImage); }
+
+    Ok(BinaryByteSliceVolume { width, height, depth, buffer, threshold })
+}
+
+
+impl BinaryVolume for BinaryByteSliceVolume<'_> {
+    #[inline]
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    #[inline]
+    fn is_inside(&self, x: u32, y: u32, z: u32) -> bool {
+        let index = (z as usize * self.height as usize + y as usize) * self.width as usize + x as usize;
+        self.buffer[index] > self.threshold
+    }
+}
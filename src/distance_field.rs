@@ -1,4 +1,8 @@
-use crate::binary_image::BinaryImage;
+use crate::binary_image::{ BinaryImage, CoverageImage };
+use crate::shape::{ Shape, FillRule, PreparedShape, Vector };
+use crate::error::{ Error, Result };
+use crate::iso_contour::Polyline;
+use std::ops::Range;
 
 
 /// Contains the distance field and the vector field produced by `SignedDistanceField::compute`.
@@ -6,12 +10,15 @@ use crate::binary_image::BinaryImage;
 /// The type parameter `D` can be used to customize the memory layout of the distance field.
 /// The library provides default Storages for `Vec<f16>` and `Vec<f23>`
 /// alias `F16DistanceStorage` and `F32DistanceStorage`.
+/// The type parameter `T` can be used to customize the memory layout of the vector field.
+/// The default `U16TargetStorage` supports images with up to 65536 pixels per side,
+/// while `U32TargetStorage` supports larger images.
 ///
 /// If any distance in this field is `INFINITY`, no shapes were found in the binary image.
 #[derive(Clone, PartialEq, Debug)]
-pub struct SignedDistanceField<D: DistanceStorage> {
-    pub width: u16,
-    pub height: u16,
+pub struct SignedDistanceField<D: DistanceStorage, T: TargetStorage = U16TargetStorage> {
+    pub width: u32,
+    pub height: u32,
 
     /// A row-major image vector with
     /// for each pixel of the original image
@@ -21,7 +28,7 @@ pub struct SignedDistanceField<D: DistanceStorage> {
     /// A row-major image vector with
     /// for each pixel of the original image
     /// containing the absolute position of the nearest edge from that pixel
-    pub distance_targets: Vec<(u16, u16)>
+    pub distance_targets: T
 }
 
 
@@ -37,29 +44,209 @@ pub type F16DistanceStorage = Vec<half::f16>;
 /// because no conversions between f16 and f32 must be made.
 pub type F32DistanceStorage = Vec<f32>;
 
+/// Store distances as a vector of `u8` numbers, mapping the distances from `-RANGE` to `RANGE` pixels
+/// linearly to the values from `2` to `254`, with edges being `128`.
+/// The values can be used directly as an 8-bit texture, without normalizing the distance field.
+/// All distances beyond the range saturate to `0` inside and `255` outside of the shape,
+/// which are interpreted as infinite distances, so these pixels do not propagate any distances.
+/// Distances are quantized to steps of `RANGE / 126` pixels.
+/// As saturated distances are infinite, such a distance field usually cannot be normalized.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct U8DistanceStorage<const RANGE: u32 = 8> {
+    pub values: Vec<u8>,
+}
+
+/// Store distances as a vector of `u16` numbers, mapping the distances from `-RANGE` to `RANGE` pixels
+/// linearly to the values from `2` to `65534`, with edges being `32768`.
+/// The values can be used directly as a 16-bit texture, without normalizing the distance field.
+/// All distances beyond the range saturate to `0` inside and `65535` outside of the shape,
+/// which are interpreted as infinite distances, so these pixels do not propagate any distances.
+/// Distances are quantized to steps of `RANGE / 32766` pixels.
+/// As saturated distances are infinite, such a distance field usually cannot be normalized.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct U16DistanceStorage<const RANGE: u32 = 64> {
+    pub values: Vec<u16>,
+}
+
+/// Store distances as a vector of signed fixed-point `i16` numbers with `SCALE` steps per pixel,
+/// with edges being `0`. The default scale supports distances up to `127` pixels with a precision of `1/256` pixels.
+/// The values can be used directly as a signed 16-bit texture, without normalizing the distance field.
+/// All distances beyond the range saturate to `i16::MIN` inside and `i16::MAX` outside of the shape,
+/// which are interpreted as infinite distances, so these pixels do not propagate any distances.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct I16DistanceStorage<const SCALE: u32 = 256> {
+    pub values: Vec<i16>,
+}
+
 
 /// Specifies how to store distances in memory.
-/// This library defines an `f16` storage and an `f32` storage.
+/// This library defines an `f16` storage and an `f32` storage,
+/// as well as quantized `u8`, `u16` and `i16` storages.
 pub trait DistanceStorage {
 
     /// Construct a new linear storage with the specified length.
     /// __All distances in this array must be initialized to `INFINITY`.__
     fn new(length: usize) -> Self;
 
-    #[inline(always)]
+    /// Resize this storage to the specified length and set all distances to `INFINITY`.
+    /// Implementations should reuse the existing memory where possible.
+    fn reset(&mut self, length: usize) where Self: Sized {
+        *self = Self::new(length);
+    }
+
     fn get(&self, index: usize) -> f32;
 
-    #[inline(always)]
     fn set(&mut self, index: usize, distance: f32);
 }
 
 
 
+/// Store distance targets as a vector of `u16` coordinates.
+/// Needs less storage, but supports only images with up to 65536 pixels per side.
+pub type U16TargetStorage = Vec<(u16, u16)>;
+
+/// Store distance targets as a vector of `u32` coordinates.
+/// Needs twice the storage, but supports images of any size.
+pub type U32TargetStorage = Vec<(u32, u32)>;
+
+
+/// Keeps the distance targets only while computing the distance field and discards them afterwards,
+/// so that the computed distance field only occupies the memory of the distances.
+/// The type parameter `T` specifies how to store the targets during computation.
+/// The distance targets of the computed distance field are not available,
+/// so `get_distance_target` and `update_exact` will panic.
+/// The `_into` functions keep the memory of the targets for the next computation,
+/// while all other functions release it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DiscardedTargetStorage<T: TargetStorage = U16TargetStorage> {
+    targets: T,
+    is_discarded: bool,
+}
+
+/// Specifies how to store distance targets in memory.
+/// This library defines an `u16` storage and an `u32` storage.
+pub trait TargetStorage {
+
+    /// Construct a new linear storage with the specified length.
+    /// All targets in this array should be initialized to `(0, 0)`.
+    fn new(length: usize) -> Self;
+
+    /// Resize this storage to the specified length and set all targets to `(0, 0)`.
+    /// Implementations should reuse the existing memory where possible.
+    fn reset(&mut self, length: usize) where Self: Sized {
+        *self = Self::new(length);
+    }
+
+    /// The largest image width and height whose pixel coordinates fit into this storage.
+    fn max_side() -> u32;
+
+    /// Called after the distance field has been computed.
+    /// Storages which only keep the targets during computation can discard them here,
+    /// but should keep their memory, so that it can be reused by the `_into` functions.
+    fn finish(&mut self) {}
+
+    /// Called after `finish` if the distance field was computed into new storage, which will not be reused.
+    /// Storages which keep the memory of discarded targets can release it here.
+    fn release(&mut self) {}
+
+    fn get(&self, index: usize) -> (u32, u32);
+
+    fn set(&mut self, index: usize, target: (u32, u32));
+}
+
+/// A distance storage whose rows can be written by multiple threads at once,
+/// as required by `SignedDistanceField::compute_exact_parallel`.
+/// Implemented by all distance storages of this library.
+#[cfg(feature = "parallel")]
+pub trait ParallelDistanceStorage: DistanceStorage {
+    /// The value which is stored for each pixel.
+    type Value: Send;
+
+    /// The stored values, in the same order as the indices of `get` and `set`.
+    fn values_mut(&mut self) -> &mut [Self::Value];
+
+    /// Converts a distance to the value which `set` would store.
+    fn value_of(distance: f32) -> Self::Value;
+}
+
+/// A target storage whose rows can be written by multiple threads at once,
+/// as required by `SignedDistanceField::compute_exact_parallel`.
+/// Implemented by all target storages of this library.
+#[cfg(feature = "parallel")]
+pub trait ParallelTargetStorage: TargetStorage {
+    /// The value which is stored for each pixel.
+    type Value: Send;
+
+    /// The stored values, in the same order as the indices of `get` and `set`.
+    fn values_mut(&mut self) -> &mut [Self::Value];
+
+    /// Converts a target to the value which `set` would store.
+    fn value_of(target: (u32, u32)) -> Self::Value;
+}
+
+
+
+/// A rectangular region of pixels in an image,
+/// used by `SignedDistanceField::update_exact` to specify which pixels have changed,
+/// and by `AtlasPacker` to describe where each distance field is placed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rectangle {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The width and height of the blocks of pixels which `SignedDistanceField::compute_truncated`
+/// either propagates completely or skips if they are far away from any edge.
+const BAND_BLOCK_SIZE: u32 = 16;
+
+/// Contains the buffers for intermediate results of computing a distance field,
+/// which can be reused by the `_into` functions of `SignedDistanceField`
+/// to avoid allocations when computing distance fields repeatedly.
+#[derive(Clone, Debug, Default)]
+pub struct ScratchContext {
+    column_edges: Vec<Option<u32>>,
+    edge_rows: Vec<Option<u32>>,
+    sampled_edge_rows: Vec<Option<u32>>,
+    edge_positions: Vec<(f32, f32)>,
+    edge_blocks: Vec<bool>,
+    band_blocks: Vec<bool>,
+    envelope: EdgeEnvelope,
+}
+
+/// Selects the pixels which `SignedDistanceField::compute_unsigned` measures the distance to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PixelSet {
+    /// The pixels inside the shape, producing zero inside and positive distances outside.
+    Inside,
+
+    /// The pixels outside the shape, producing zero outside and positive distances inside.
+    Outside,
+}
+
+/// Specifies which borders of an image wrap around to the opposite side,
+/// as used by `SignedDistanceField::compute_wrapped` for seamlessly tiling images.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Wrap {
+    /// No border wraps around, the image ends at its borders.
+    None,
+
+    /// The left and right borders wrap around, so the image tiles horizontally.
+    Horizontal,
+
+    /// The top and bottom borders wrap around, so the image tiles vertically.
+    Vertical,
+
+    /// All borders wrap around, so the image tiles in both directions.
+    Both,
+}
+
 /// Represents a distance field which was normalized to the range `[0, 1]`.
 /// Also contains information about the greatest distances of the unnormalized distance field.
-pub struct NormalizedDistanceField<D: DistanceStorage> {
-    pub width: u16,
-    pub height: u16,
+pub struct NormalizedDistanceField<D: DistanceStorage, T: TargetStorage = U16TargetStorage> {
+    pub width: u32,
+    pub height: u32,
 
     /// All distances are in the range of `[0..1]`.
     pub distances: D,
@@ -83,219 +270,1238 @@ pub struct NormalizedDistanceField<D: DistanceStorage> {
     /// A row-major image vector with
     /// for each pixel of the original image
     /// containing the absolute position of the nearest edge from that pixel
-    pub distance_targets: Vec<(u16, u16)>
+    pub distance_targets: T
 }
 
 
 
 
-impl<D> SignedDistanceField<D> where D: DistanceStorage {
+impl<D, T> SignedDistanceField<D, T> where D: DistanceStorage, T: TargetStorage {
+
+    /// Create a distance field with all distances being `INFINITY`.
+    /// Panics if the target storage cannot store coordinates of this size.
+    pub(crate) fn new(width: u32, height: u32) -> Self {
+        assert!(
+            width <= T::max_side() && height <= T::max_side(),
+            "Image too large for target storage"
+        );
+
+        SignedDistanceField {
+            width, height,
+            distances: D::new(width as usize * height as usize),
+            distance_targets: T::new(width as usize * height as usize),
+        }
+    }
+
+    /// Resize this distance field and set all distances to `INFINITY`, reusing the existing storage.
+    /// Panics if the target storage cannot store coordinates of this size.
+    fn reset(&mut self, width: u32, height: u32) {
+        assert!(
+            width <= T::max_side() && height <= T::max_side(),
+            "Image too large for target storage"
+        );
+
+        self.width = width;
+        self.height = height;
+        self.distances.reset(width as usize * height as usize);
+        self.distance_targets.reset(width as usize * height as usize);
+    }
 
     /// Approximates the signed distance field of the specified image.
     /// The algorithm used is based on the paper "The dead reckoning signed distance transform"
     /// by George J. Grevara, 2004.
     pub fn compute(binary_image: &impl BinaryImage) -> Self {
+        let mut distance_field = Self::new(0, 0);
+        distance_field.compute_into(binary_image);
+        distance_field.distance_targets.release();
+        distance_field
+    }
+
+    /// Approximates the signed distance field of the specified image, like `compute`,
+    /// but reuses the storage of this distance field instead of allocating a new one.
+    /// The storage is only resized if the image has different dimensions.
+    pub fn compute_into(&mut self, binary_image: &impl BinaryImage) {
         let width = binary_image.width();
         let height = binary_image.height();
 
-        let mut distance_field = SignedDistanceField {
-            width, height,
-            distances: D::new(width as usize * height as usize),
-            distance_targets: vec![(0, 0); width as usize * height as usize],
+        self.reset(width, height);
+
+        // for every pixel directly at an edge, set its distance to zero
+        for y in 0..height {
+            for x in 0..width {
+                if is_at_any_edge(binary_image, x, y, Wrap::None) {
+                    self.set_target_with_distance(x, y, x, y, 0.0);
+                }
+            }
+        }
+
+        self.propagate(Wrap::None, distance);
+        self.flip_inside_distance_signs(binary_image);
+        self.distance_targets.finish();
+    }
+
+    /// Approximates the signed distance field of the specified image, like `compute`,
+    /// but only within the specified radius around the edges.
+    /// All distances further away are saturated to `radius` outside and `-radius` inside of the shape,
+    /// and the distance targets of those pixels are not meaningful.
+    /// Only the blocks of pixels near an edge are propagated,
+    /// which is much quicker for images with few edges.
+    /// If the image does not contain any shapes, all distances are saturated.
+    /// A negative radius is treated as zero.
+    pub fn compute_truncated(binary_image: &impl BinaryImage, radius: f32) -> Self {
+        let mut distance_field = Self::new(0, 0);
+        distance_field.compute_truncated_into(binary_image, radius, &mut ScratchContext::default());
+        distance_field.distance_targets.release();
+        distance_field
+    }
+
+    /// Approximates the truncated signed distance field of the specified image, like `compute_truncated`,
+    /// but reuses the storage of this distance field and the buffers of the scratch context.
+    pub fn compute_truncated_into(&mut self, binary_image: &impl BinaryImage, radius: f32, scratch: &mut ScratchContext) {
+        let radius = radius.max(0.0);
+        let width = binary_image.width();
+        let height = binary_image.height();
+
+        self.reset(width, height);
+
+        let blocks_x = width.div_ceil(BAND_BLOCK_SIZE);
+        let blocks_y = height.div_ceil(BAND_BLOCK_SIZE);
+        let edge_blocks = &mut scratch.edge_blocks;
+        edge_blocks.clear();
+        edge_blocks.resize(blocks_x as usize * blocks_y as usize, false);
+
+        // for every pixel directly at an edge, set its distance to zero and remember its block
+        for y in 0..height {
+            for x in 0..width {
+                if is_at_any_edge(binary_image, x, y, Wrap::None) {
+                    self.set_target_with_distance(x, y, x, y, 0.0);
+                    edge_blocks[(y / BAND_BLOCK_SIZE * blocks_x + x / BAND_BLOCK_SIZE) as usize] = true;
+                }
+            }
+        }
+
+        // every pixel within the radius of an edge is at most this many blocks away from the edge block
+        let block_radius = (radius / BAND_BLOCK_SIZE as f32).ceil() as u32;
+        let band_blocks = &mut scratch.band_blocks;
+        band_blocks.clear();
+        band_blocks.resize(edge_blocks.len(), false);
+
+        for block_y in 0..blocks_y {
+            for block_x in 0..blocks_x {
+                if !edge_blocks[(block_y * blocks_x + block_x) as usize] { continue; }
+
+                let rows = block_y.saturating_sub(block_radius) ..= (block_y + block_radius).min(blocks_y - 1);
+                for band_y in rows {
+                    let columns = block_x.saturating_sub(block_radius) ..= (block_x + block_radius).min(blocks_x - 1);
+                    for band_x in columns {
+                        band_blocks[(band_y * blocks_x + band_x) as usize] = true;
+                    }
+                }
+            }
+        }
+
+        let band_columns = |y: u32, block_x: u32| {
+            if band_blocks[(y / BAND_BLOCK_SIZE * blocks_x + block_x) as usize] {
+                block_x * BAND_BLOCK_SIZE .. ((block_x + 1) * BAND_BLOCK_SIZE).min(width)
+            }
+            else { 0..0 }
         };
 
+        // perform forwards and backwards iteration only inside of the band
+        for y in 0..height {
+            for block_x in 0..blocks_x {
+                for x in band_columns(y, block_x) {
+                    self.propagate_forwards(&distance, x, y, Wrap::None);
+                }
+            }
+        }
+
+        for y in (0..height).rev() {
+            for block_x in (0..blocks_x).rev() {
+                for x in band_columns(y, block_x).rev() {
+                    self.propagate_backwards(&distance, x, y, Wrap::None);
+                }
+            }
+        }
+
+        // saturate all distances outside of the band, including pixels which were never reached
+        for index in 0..width as usize * height as usize {
+            let distance = self.distances.get(index);
+            if distance > radius { self.distances.set(index, radius); }
+        }
+
+        self.flip_inside_distance_signs(binary_image);
+        self.distance_targets.finish();
+    }
+
+    /// Approximates the signed distance field of the specified image, like `compute`,
+    /// but treats the image as periodic, wrapping around the borders as specified.
+    /// Edges across a wrapping border are detected, and distances are measured across that border,
+    /// so the resulting distance field tiles seamlessly if the image does.
+    /// Like `compute`, this uses dead reckoning, so the distances are approximate, not exact.
+    /// Targets across a wrapping border are propagated with a second forward and backward pass,
+    /// which makes the accuracy similar to `compute` on an image without wrapping borders.
+    pub fn compute_wrapped(binary_image: &impl BinaryImage, wrap: Wrap) -> Self {
+        let mut distance_field = Self::new(0, 0);
+        distance_field.compute_wrapped_into(binary_image, wrap);
+        distance_field.distance_targets.release();
+        distance_field
+    }
+
+    /// Approximates the signed distance field of the specified periodic image, like `compute_wrapped`,
+    /// but reuses the storage of this distance field instead of allocating a new one.
+    pub fn compute_wrapped_into(&mut self, binary_image: &impl BinaryImage, wrap: Wrap) {
+        let width = binary_image.width();
+        let height = binary_image.height();
+
+        self.reset(width, height);
+
         // for every pixel directly at an edge, set its distance to zero
         for y in 0..height {
             for x in 0..width {
-                if     is_at_edge(binary_image, x, y, -1,  0)
-                    || is_at_edge(binary_image, x, y,  1,  0)
-                    || is_at_edge(binary_image, x, y,  0, -1)
-                    || is_at_edge(binary_image, x, y,  0,  1)
-                {
-                    distance_field.set_target_with_distance(x, y, x, y, 0.0);
+                if is_at_any_edge(binary_image, x, y, wrap) {
+                    self.set_target_with_distance(x, y, x, y, 0.0);
+                }
+            }
+        }
+
+        let target_distance = |x, y, target_x, target_y|
+            wrapped_distance(x, y, target_x, target_y, width, height, wrap);
+
+        self.propagate(wrap, target_distance);
+
+        // targets across a wrapping border may only have arrived at a pixel
+        // after it was visited, so they need to be propagated once more
+        if wrap != Wrap::None {
+            self.propagate(wrap, target_distance);
+        }
+
+        self.flip_inside_distance_signs(binary_image);
+        self.distance_targets.finish();
+    }
+
+    /// Approximates the signed distance field of the specified anti-aliased image.
+    /// The coverage of the pixels at an edge is used to estimate the sub-pixel position of the edge,
+    /// and all distances are measured to these estimated positions instead of to the edge pixel centers,
+    /// which avoids stair-stepping. The distance targets still contain the edge pixels.
+    /// The edge estimation is based on the paper "Anti-aliased Euclidean distance transform"
+    /// by Stefan Gustavson and Robin Strand, 2011.
+    pub fn compute_anti_aliased(image: &impl CoverageImage) -> Self {
+        let mut distance_field = Self::new(0, 0);
+        distance_field.compute_anti_aliased_into(image, &mut ScratchContext::default());
+        distance_field.distance_targets.release();
+        distance_field
+    }
+
+    /// Approximates the signed distance field of the specified anti-aliased image, like `compute_anti_aliased`,
+    /// but reuses the storage of this distance field and the buffers of the scratch context.
+    pub fn compute_anti_aliased_into(&mut self, image: &impl CoverageImage, scratch: &mut ScratchContext) {
+        let width = image.width();
+        let height = image.height();
+
+        self.reset(width, height);
+
+        // for every pixel at an edge or partially covered, estimate the position of the edge
+        let edge_positions = &mut scratch.edge_positions;
+        edge_positions.clear();
+        edge_positions.resize(width as usize * height as usize, (0.0, 0.0));
+        for y in 0..height {
+            for x in 0..width {
+                let coverage = image.coverage(x, y);
+
+                if (coverage > 0.0 && coverage < 1.0) || is_at_any_edge(image, x, y, Wrap::None) {
+                    let (gradient_x, gradient_y) = coverage_gradient(image, x, y);
+                    let edge_distance = edge_distance(gradient_x, gradient_y, coverage);
+
+                    let index = self.flatten_index(x, y);
+                    edge_positions[index] = (
+                        x as f32 + gradient_x * edge_distance,
+                        y as f32 + gradient_y * edge_distance
+                    );
+
+                    self.set_target_with_distance(x, y, x, y, edge_distance.abs());
                 }
             }
         }
 
-        // perform forwards iteration
-        for y in 0..height {
-            for x in 0..width {
-                // encourage auto vectorization and fetching all distances in parallel
-                let left_bottom  = distance_field.distance_by_neighbour(x, y, -1, -1);
-                let bottom       = distance_field.distance_by_neighbour(x, y,  0, -1);
-                let right_bottom = distance_field.distance_by_neighbour(x, y,  1, -1);
-                let left         = distance_field.distance_by_neighbour(x, y, -1,  0);
-                let mut own      = distance_field.get_distance(x, y);
-
-                // if any of the neighbour is smaller, update ourselves
-                // TODO only write the true smallest instead of overwriting previous distances?
-                if left_bottom  < own { own = distance_field.take_neighbour_target(x, y, -1, -1); }
-                if bottom       < own { own = distance_field.take_neighbour_target(x, y,  0, -1); }
-                if right_bottom < own { own = distance_field.take_neighbour_target(x, y,  1, -1); }
-                if left         < own {       distance_field.take_neighbour_target(x, y, -1,  0); }
-            }
+        self.propagate(Wrap::None, |x, y, target_x, target_y| {
+            let (edge_x, edge_y) = edge_positions[width as usize * target_y as usize + target_x as usize];
+            ((x as f32 - edge_x).powi(2) + (y as f32 - edge_y).powi(2)).sqrt()
+        });
+
+        self.flip_inside_distance_signs(image);
+        self.distance_targets.finish();
+    }
+
+    /// Computes the exact signed distance field of the specified image.
+    /// In contrast to `compute`, every distance is the exact euclidean distance
+    /// to the nearest edge pixel, which makes this a bit slower.
+    /// The algorithm used is based on the paper "Distance Transforms of Sampled Functions"
+    /// by Pedro F. Felzenszwalb and Daniel P. Huttenlocher, 2012.
+    pub fn compute_exact(binary_image: &impl BinaryImage) -> Self {
+        let mut distance_field = Self::new(0, 0);
+        distance_field.compute_exact_into(binary_image, &mut ScratchContext::default());
+        distance_field.distance_targets.release();
+        distance_field
+    }
+
+    /// Computes the exact signed distance field of the specified image, like `compute_exact`,
+    /// but reuses the storage of this distance field and the buffers of the scratch context.
+    pub fn compute_exact_into(&mut self, binary_image: &impl BinaryImage, scratch: &mut ScratchContext) {
+        self.compute_exact_features_into(
+            binary_image.width(), binary_image.height(),
+            |x, y| is_at_any_edge(binary_image, x, y, Wrap::None), scratch
+        );
+
+        self.flip_inside_distance_signs(binary_image);
+        self.distance_targets.finish();
+    }
+
+    /// Computes the exact unsigned distance from every pixel to the center of the nearest pixel in the specified set.
+    /// With `PixelSet::Inside`, pixels inside the shape have a distance of zero
+    /// and pixels outside have the distance to the nearest inside pixel.
+    /// With `PixelSet::Outside`, it is the other way around.
+    /// In contrast to `compute_exact`, distances are not measured to the edge pixels on both sides of the outline,
+    /// and the distance targets are the nearest pixels of the set.
+    /// If the image does not contain any pixel of the set, all distances are `INFINITY`.
+    pub fn compute_unsigned(binary_image: &impl BinaryImage, set: PixelSet) -> Self {
+        let mut distance_field = Self::new(0, 0);
+        distance_field.compute_unsigned_into(binary_image, set, &mut ScratchContext::default());
+        distance_field.distance_targets.release();
+        distance_field
+    }
+
+    /// Computes the exact unsigned distance field of the specified image, like `compute_unsigned`,
+    /// but reuses the storage of this distance field and the buffers of the scratch context.
+    pub fn compute_unsigned_into(&mut self, binary_image: &impl BinaryImage, set: PixelSet, scratch: &mut ScratchContext) {
+        let inside = set == PixelSet::Inside;
+
+        self.compute_exact_features_into(
+            binary_image.width(), binary_image.height(),
+            |x, y| binary_image.is_inside(x, y) == inside, scratch
+        );
+
+        self.distance_targets.finish();
+    }
+
+    /// Computes the exact euclidean distance from every pixel to the nearest feature pixel,
+    /// using the algorithm by Felzenszwalb and Huttenlocher.
+    /// All distances are positive, and pixels without any feature are `INFINITY`.
+    #[inline(always)]
+    pub(crate) fn compute_exact_features_into(
+        &mut self, width: u32, height: u32,
+        is_feature: impl Fn(u32, u32) -> bool, scratch: &mut ScratchContext
+    ) {
+        self.reset(width, height);
+
+        // for every pixel, find the nearest feature in the same column,
+        // storing the features column by column
+        let column_edges = &mut scratch.column_edges;
+        column_edges.clear();
+        column_edges.resize(width as usize * height as usize, None);
+
+        if height != 0 { // chunk size must not be zero
+            for (x, column) in column_edges.chunks_mut(height as usize).enumerate() {
+                nearest_column_edges(column, |y| is_feature(x as u32, y));
+            }
+        }
+
+        // for every row, find the nearest of all the column edges
+        for y in 0..height {
+            nearest_row_edges(&mut scratch.envelope, column_edges, width, height, y, |x, (edge_x, edge_y)| {
+                self.set_target_and_distance(x, y, edge_x, edge_y);
+            });
+        }
+    }
+
+    /// Computes the exact signed distance field of the specified high resolution image,
+    /// producing a distance field which is smaller by the specified integer factor in both dimensions.
+    /// Each pixel of the result takes the distance of the high resolution pixel at its center,
+    /// divided by the factor, so that distances are measured in pixels of the result.
+    /// Distance targets are also measured in pixels of the result.
+    /// Only the nearest edges of the sampled rows are kept while computing,
+    /// so the memory required is a fraction of the high resolution distance field.
+    /// Panics if the factor is zero.
+    pub fn compute_downscaled(binary_image: &impl BinaryImage, factor: u32) -> Self {
+        let mut distance_field = Self::new(0, 0);
+        distance_field.compute_downscaled_into(binary_image, factor, &mut ScratchContext::default());
+        distance_field.distance_targets.release();
+        distance_field
+    }
+
+    /// Computes the downscaled exact signed distance field of the specified image, like `compute_downscaled`,
+    /// but reuses the storage of this distance field and the buffers of the scratch context.
+    pub fn compute_downscaled_into(&mut self, binary_image: &impl BinaryImage, factor: u32, scratch: &mut ScratchContext) {
+        assert_ne!(factor, 0, "Downscale factor must not be zero");
+
+        let width = binary_image.width();
+        let height = binary_image.height();
+        let (sampled_width, sampled_height) = (width.div_ceil(factor), height.div_ceil(factor));
+
+        // the high resolution pixel at the center of a pixel of the result
+        let sampled = |coordinate: u32, size: u32| (coordinate * factor + factor / 2).min(size - 1);
+
+        self.reset(sampled_width, sampled_height);
+
+        // for every pixel of a sampled row, find the nearest edge below it in the same column
+        let nearest_edge_rows = &mut scratch.edge_rows;
+        nearest_edge_rows.clear();
+        nearest_edge_rows.resize(width as usize, None);
+
+        let sampled_edge_rows = &mut scratch.sampled_edge_rows;
+        sampled_edge_rows.clear();
+        sampled_edge_rows.resize(width as usize * sampled_height as usize, None);
+
+        let mut sampled_row = sampled_height;
+        for y in (0..height).rev() {
+            for x in 0..width {
+                if is_at_any_edge(binary_image, x, y, Wrap::None) {
+                    nearest_edge_rows[x as usize] = Some(y);
+                }
+            }
+
+            if sampled_row > 0 && y == sampled(sampled_row - 1, height) {
+                sampled_row -= 1;
+                let start = sampled_row as usize * width as usize;
+                sampled_edge_rows[start .. start + width as usize].copy_from_slice(nearest_edge_rows);
+            }
+        }
+
+        // replace it with the nearest edge above, if that one is closer
+        nearest_edge_rows.iter_mut().for_each(|edge_y| *edge_y = None);
+        for y in 0..height {
+            for x in 0..width {
+                if is_at_any_edge(binary_image, x, y, Wrap::None) {
+                    nearest_edge_rows[x as usize] = Some(y);
+                }
+            }
+
+            if sampled_row < sampled_height && y == sampled(sampled_row, height) {
+                let start = sampled_row as usize * width as usize;
+
+                for (below, &above) in sampled_edge_rows[start .. start + width as usize].iter_mut().zip(nearest_edge_rows.iter()) {
+                    if let Some(above) = above {
+                        if below.map_or(true, |below| y - above <= below - y) { *below = Some(above); }
+                    }
+                }
+
+                sampled_row += 1;
+            }
+        }
+
+        // for every sampled row, find the nearest of all the column edges at the sampled columns
+        let envelope = &mut scratch.envelope;
+
+        for sampled_y in 0..sampled_height {
+            let y = sampled(sampled_y, height);
+            let start = sampled_y as usize * width as usize;
+            envelope.clear();
+
+            for column in 0..width {
+                if let Some(edge_y) = sampled_edge_rows[start + column as usize] {
+                    envelope.push(column, edge_y, y);
+                }
+            }
+
+            let mut sampled_x = 0;
+            envelope.for_each_nearest_edge(0..width, |x, (edge_x, edge_y)| {
+                if sampled_x < sampled_width && x == sampled(sampled_x, width) {
+                    let distance = distance(x, y, edge_x, edge_y) / factor as f32;
+                    self.set_target_with_distance(sampled_x, sampled_y, edge_x / factor, edge_y / factor, distance);
+                    sampled_x += 1;
+                }
+            });
+
+            for sampled_x in 0..sampled_width {
+                if binary_image.is_inside(sampled(sampled_x, width), y) {
+                    self.invert_distance_sign(sampled_x, sampled_y);
+                }
+            }
+        }
+
+        self.distance_targets.finish();
+    }
+
+    /// Updates this exact distance field after the specified image has changed inside the specified region,
+    /// producing the same distances as `compute_exact` would for the whole changed image.
+    /// Only the pixels whose nearest edge may have been removed
+    /// or which may be closer to a new edge are recomputed,
+    /// searching for edges only as far as required to find the nearest one.
+    /// This distance field must have been computed with `compute_exact` from the previous image.
+    /// Panics if the image does not have the dimensions of this distance field.
+    pub fn update_exact(&mut self, binary_image: &impl BinaryImage, changed: Rectangle) {
+        assert!(
+            binary_image.width() == self.width && binary_image.height() == self.height,
+            "Image dimension mismatch"
+        );
+
+        let changed = changed.clamped(self.width, self.height);
+        if changed.is_empty() { return; }
+
+        // edges may appear or disappear in the changed region and directly next to it
+        let edges = changed.grown(1, self.width, self.height);
+
+        // find all pixels whose nearest edge may have disappeared or which may be closer to a new edge,
+        // which are the pixels that are at least as far from their nearest edge as from the changed edges
+        let mut affected: Option<Rectangle> = None;
+        let mut largest_distance: f32 = 1.0;
+
+        // search rings of pixels around the changed edges, moving outwards,
+        // until a ring does not contain any pixel which is close to being affected.
+        // the affected pixels are connected to the changed edges, and the difference between
+        // both distances changes by at most one between pixels and the nearest point of the ring,
+        // so no pixel beyond such a ring is affected
+        let mut inner: Option<Rectangle> = None;
+        let mut margin = 0;
+
+        loop {
+            let ring = edges.grown(margin, self.width, self.height);
+            if inner == Some(ring) { break; } // the ring covers the whole image
+
+            let mut is_any_nearly_affected = false;
+
+            for_each_ring_pixel(ring, inner, |x, y| {
+                let distance = self.get_distance(x, y).abs();
+                let (target_x, target_y) = self.get_distance_target(x, y);
+                let edge_distance = edges.distance_to(x, y);
+
+                let is_affected = distance.is_infinite()
+                    || edges.contains(target_x, target_y)
+                    || edge_distance <= distance;
+
+                if is_affected {
+                    affected = Some(affected.map_or(Rectangle::new(x, y, 1, 1), |affected| affected.including(x, y)));
+                    if distance.is_finite() { largest_distance = largest_distance.max(distance); }
+                }
+
+                if edge_distance - 1.5 <= distance {
+                    is_any_nearly_affected = true;
+                }
+            });
+
+            if !is_any_nearly_affected { break; }
+
+            inner = Some(ring);
+            margin += 1;
+        }
+
+        let affected = match affected {
+            Some(affected) => affected,
+            None => return,
+        };
+
+        // the nearest edges of the affected pixels may be outside of the affected region,
+        // so search a larger region until every nearest edge is closer than any pixel outside of it
+        let mut margin = largest_distance.ceil() as u32;
+        let nearest_edges = loop {
+            let searched = affected.grown(margin, self.width, self.height);
+            let nearest_edges = nearest_edges_within(binary_image, affected, searched);
+
+            let is_exact = nearest_edges.iter().enumerate().all(|(index, nearest_edge)| {
+                let x = affected.x + index as u32 % affected.width;
+                let y = affected.y + index as u32 / affected.width;
+                let outside_distance = searched.distance_to_outside(x, y, self.width, self.height);
+
+                match *nearest_edge {
+                    Some((edge_x, edge_y)) => distance(x, y, edge_x, edge_y) <= outside_distance,
+                    None => outside_distance.is_infinite(),
+                }
+            });
+
+            if is_exact { break nearest_edges; }
+            margin = margin.saturating_mul(2);
+        };
+
+        for (index, nearest_edge) in nearest_edges.into_iter().enumerate() {
+            let x = affected.x + index as u32 % affected.width;
+            let y = affected.y + index as u32 / affected.width;
+
+            match nearest_edge {
+                Some((edge_x, edge_y)) => { self.set_target_and_distance(x, y, edge_x, edge_y); },
+                None => self.set_target_with_distance(x, y, 0, 0, f32::INFINITY),
+            }
+
+            if binary_image.is_inside(x, y) {
+                self.invert_distance_sign(x, y);
+            }
+        }
+    }
+
+    /// Computes the exact signed distance field of the specified image, using all available cores.
+    /// Produces the same distance field as `compute_exact`.
+    /// Requires the feature `parallel` to be activated.
+    #[cfg(feature = "parallel")]
+    pub fn compute_exact_parallel(binary_image: &(impl BinaryImage + Sync)) -> Self
+        where D: ParallelDistanceStorage, T: ParallelTargetStorage
+    {
+        let mut distance_field = Self::new(0, 0);
+        distance_field.compute_exact_parallel_into(binary_image, &mut ScratchContext::default());
+        distance_field.distance_targets.release();
+        distance_field
+    }
+
+    /// Computes the exact signed distance field of the specified image using all available cores,
+    /// like `compute_exact_parallel`, but reuses the storage of this distance field and the buffers of the scratch context.
+    /// Requires the feature `parallel` to be activated.
+    #[cfg(feature = "parallel")]
+    pub fn compute_exact_parallel_into(&mut self, binary_image: &(impl BinaryImage + Sync), scratch: &mut ScratchContext)
+        where D: ParallelDistanceStorage, T: ParallelTargetStorage
+    {
+        use rayon::prelude::*;
+
+        let width = binary_image.width();
+        let height = binary_image.height();
+        self.reset(width, height);
+
+        // for every pixel, find the nearest edge in the same column,
+        // storing the edges column by column, such that each column can be processed in parallel
+        let column_edges = &mut scratch.column_edges;
+        column_edges.clear();
+        column_edges.resize(width as usize * height as usize, None);
+
+        if height != 0 { // chunk size must not be zero
+            column_edges.par_chunks_mut(height as usize).enumerate().for_each(|(x, column)| {
+                nearest_column_edges(column, |y| is_at_any_edge(binary_image, x as u32, y, Wrap::None));
+            });
+        }
+
+        // for every row, find the nearest of all the column edges,
+        // writing the distances and targets of each row in parallel
+        let column_edges = &*column_edges;
+
+        if width != 0 { // chunk size must not be zero
+            self.distances.values_mut().par_chunks_mut(width as usize)
+                .zip(self.distance_targets.values_mut().par_chunks_mut(width as usize))
+                .enumerate()
+                .for_each_init(
+                    || EdgeEnvelope::with_capacity(width as usize),
+                    |envelope, (y, (distances, targets))| {
+                        let y = y as u32;
+
+                        nearest_row_edges(envelope, column_edges, width, height, y, |x, (edge_x, edge_y)| {
+                            let distance = distance(x, y, edge_x, edge_y);
+                            let signed_distance = if binary_image.is_inside(x, y) { -distance } else { distance };
+
+                            distances[x as usize] = D::value_of(signed_distance);
+                            targets[x as usize] = T::value_of((edge_x, edge_y));
+                        });
+                    }
+                );
+        }
+
+        self.distance_targets.finish();
+    }
+
+    /// Computes the exact signed distance field of the specified vector shape.
+    /// The shape coordinates are measured in pixels of the distance field,
+    /// and the fill rule decides which areas enclosed by the contours are inside the shape.
+    /// The distance targets contain the pixel of the nearest point on the outline,
+    /// clamped to the bounds of the distance field.
+    pub fn compute_shape(shape: &Shape, width: u32, height: u32, fill_rule: FillRule) -> Self {
+        let shape = PreparedShape::new(shape);
+
+        let mut distance_field = Self::new(width, height);
+
+        let mut inside = vec![false; width as usize];
+        let mut nearest_segment = 0;
+
+        for y in 0..height {
+            shape.fill_row(y as f64 + 0.5, fill_rule, &mut inside);
+
+            for x in 0..width {
+                let pixel = Vector::new(x as f64 + 0.5, y as f64 + 0.5);
+
+                if let Some((target, distance)) = shape.nearest(pixel, &mut nearest_segment) {
+                    let target_x = target.x.floor().clamp(0.0, width as f64 - 1.0) as u32;
+                    let target_y = target.y.floor().clamp(0.0, height as f64 - 1.0) as u32;
+                    let distance = if inside[x as usize] { - distance } else { distance };
+
+                    distance_field.set_target_with_distance(x, y, target_x, target_y, distance as f32);
+                }
+
+                else if inside[x as usize] {
+                    distance_field.invert_distance_sign(x, y);
+                }
+            }
+        }
+
+        distance_field.distance_targets.finish();
+        distance_field.distance_targets.release();
+        distance_field
+    }
+
+    /// Approximates the signed distance field of the specified image, like `compute`.
+    /// Returns an error if the image has no pixels, is too large for the target storage, or does not contain any shapes.
+    pub fn try_compute(binary_image: &impl BinaryImage) -> Result<Self> {
+        Self::check_dimensions(binary_image.width(), binary_image.height())?;
+        Self::compute(binary_image).with_any_shape()
+    }
+
+    /// Approximates the signed distance field of the specified anti-aliased image, like `compute_anti_aliased`.
+    /// Returns an error if the image has no pixels, is too large for the target storage, or does not contain any shapes.
+    pub fn try_compute_anti_aliased(image: &impl CoverageImage) -> Result<Self> {
+        Self::check_dimensions(image.width(), image.height())?;
+        Self::compute_anti_aliased(image).with_any_shape()
+    }
+
+    /// Approximates the signed distance field of the specified image with wrapping borders, like `compute_wrapped`.
+    /// Returns an error if the image has no pixels, is too large for the target storage, or does not contain any shapes.
+    pub fn try_compute_wrapped(binary_image: &impl BinaryImage, wrap: Wrap) -> Result<Self> {
+        Self::check_dimensions(binary_image.width(), binary_image.height())?;
+        Self::compute_wrapped(binary_image, wrap).with_any_shape()
+    }
+
+    /// Computes the exact signed distance field of the specified image, like `compute_exact`.
+    /// Returns an error if the image has no pixels, is too large for the target storage, or does not contain any shapes.
+    pub fn try_compute_exact(binary_image: &impl BinaryImage) -> Result<Self> {
+        Self::check_dimensions(binary_image.width(), binary_image.height())?;
+        Self::compute_exact(binary_image).with_any_shape()
+    }
+
+    /// Computes the exact signed distance field of the specified image using all available cores, like `compute_exact_parallel`.
+    /// Returns an error if the image has no pixels, is too large for the target storage, or does not contain any shapes.
+    /// Requires the feature `parallel` to be activated.
+    #[cfg(feature = "parallel")]
+    pub fn try_compute_exact_parallel(binary_image: &(impl BinaryImage + Sync)) -> Result<Self>
+        where D: ParallelDistanceStorage, T: ParallelTargetStorage
+    {
+        Self::check_dimensions(binary_image.width(), binary_image.height())?;
+        Self::compute_exact_parallel(binary_image).with_any_shape()
+    }
+
+    /// Computes the exact signed distance field of the specified vector shape, like `compute_shape`.
+    /// Returns an error if the distance field has no pixels, is too large for the target storage,
+    /// or the shape does not contain any segments.
+    pub fn try_compute_shape(shape: &Shape, width: u32, height: u32, fill_rule: FillRule) -> Result<Self> {
+        Self::check_dimensions(width, height)?;
+        Self::compute_shape(shape, width, height, fill_rule).with_any_shape()
+    }
+
+    /// Returns an error if a distance field with the specified dimensions has no pixels
+    /// or cannot be stored in the target storage.
+    fn check_dimensions(width: u32, height: u32) -> Result<()> {
+        check_not_empty(width, height)?;
+
+        let side = width.max(height);
+        if side > T::max_side() { Err(Error::DimensionTooLarge { side, max_side: T::max_side() }) }
+        else { Ok(()) }
+    }
+
+    /// Returns an error if no distance is finite.
+    /// Quantized storages saturate distant pixels to infinity,
+    /// so a single infinite distance does not mean that the image contains no shapes.
+    fn with_any_shape(self) -> Result<Self> {
+        let pixel_count = self.width as usize * self.height as usize;
+
+        if (0..pixel_count).any(|index| self.distances.get(index).is_finite()) { Ok(self) }
+        else { Err(Error::NoShapeFound) }
+    }
+
+    /// Flip the distance sign of every pixel which is inside the shape.
+    fn flip_inside_distance_signs(&mut self, binary_image: &impl BinaryImage) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if binary_image.is_inside(x, y) {
+                    self.invert_distance_sign(x, y);
+                }
+            }
+        }
+    }
+
+    /// Propagate the targets of all edge pixels to the whole image,
+    /// using a forward pass and a backward pass of dead reckoning.
+    /// The function computes the distance from a pixel to the edge of a target pixel.
+    /// Neighbours wrap around the borders of the image as specified.
+    #[inline(always)]
+    fn propagate(&mut self, wrap: Wrap, target_distance: impl Fn(u32, u32, u32, u32) -> f32) {
+        // perform forwards iteration
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.propagate_forwards(&target_distance, x, y, wrap);
+            }
+        }
+
+        // perform backwards iteration
+        for y in (0..self.height).rev() {
+            for x in (0..self.width).rev() {
+                self.propagate_backwards(&target_distance, x, y, wrap);
+            }
+        }
+    }
+
+    /// Take the target of the left or any bottom neighbour, if that one is nearer.
+    #[inline(always)]
+    fn propagate_forwards(&mut self, target_distance: &impl Fn(u32, u32, u32, u32) -> f32, x: u32, y: u32, wrap: Wrap) {
+        // encourage auto vectorization and fetching all distances in parallel
+        let left_bottom  = self.distance_by_neighbour(x, y, -1, -1, wrap);
+        let bottom       = self.distance_by_neighbour(x, y,  0, -1, wrap);
+        let right_bottom = self.distance_by_neighbour(x, y,  1, -1, wrap);
+        let left         = self.distance_by_neighbour(x, y, -1,  0, wrap);
+        let mut own      = self.get_distance(x, y);
+
+        // if any of the neighbour is smaller, update ourselves
+        // TODO only write the true smallest instead of overwriting previous distances?
+        if left_bottom  < own { own = self.take_neighbour_target(target_distance, x, y, -1, -1, wrap); }
+        if bottom       < own { own = self.take_neighbour_target(target_distance, x, y,  0, -1, wrap); }
+        if right_bottom < own { own = self.take_neighbour_target(target_distance, x, y,  1, -1, wrap); }
+        if left         < own {       self.take_neighbour_target(target_distance, x, y, -1,  0, wrap); }
+    }
+
+    /// Take the target of the right or any top neighbour, if that one is nearer.
+    #[inline(always)]
+    fn propagate_backwards(&mut self, target_distance: &impl Fn(u32, u32, u32, u32) -> f32, x: u32, y: u32, wrap: Wrap) {
+        // encourage auto vectorization and fetching all distances in parallel
+        let right    = self.distance_by_neighbour(x, y,  1,  0, wrap);
+        let top_left = self.distance_by_neighbour(x, y, -1,  1, wrap);
+        let top      = self.distance_by_neighbour(x, y,  0,  1, wrap);
+        let top_right= self.distance_by_neighbour(x, y,  1,  1, wrap);
+        let mut own  = self.get_distance(x, y);
+
+        // if any of the neighbour is smaller, update ourselves
+        // TODO only write the true smallest instead of overwriting previous distances?
+        if right     < own { own = self.take_neighbour_target(target_distance, x, y,  1,  0, wrap); }
+        if top_left  < own { own = self.take_neighbour_target(target_distance, x, y, -1,  1, wrap); }
+        if top       < own { own = self.take_neighbour_target(target_distance, x, y,  0,  1, wrap); }
+        if top_right < own {       self.take_neighbour_target(target_distance, x, y,  1,  1, wrap); }
+    }
+
+    /// Returns a potentially smaller distance, based on the neighbour's distance.
+    /// If there is no neighbour (at the bounds of the image), `INFINITY` is returned.
+    #[inline(always)]
+    fn distance_by_neighbour(&mut self, x: u32, y: u32, neighbour_x: i64, neighbour_y: i64, wrap: Wrap) -> f32 {
+        // this should be const per function call, as `neighbour` is const per function call
+        let distance_to_neighbour = length(neighbour_x, neighbour_y);
+
+        // if neighbour exists, return the potentially smaller distance to the target
+        if let Some((neighbour_x, neighbour_y)) = neighbour_position(
+            x, y, neighbour_x, neighbour_y, self.width, self.height, wrap
+        ) {
+            let neighbours_distance = self.get_distance(neighbour_x, neighbour_y);
+            neighbours_distance + distance_to_neighbour
+        }
+
+        else {
+            f32::INFINITY
+        }
+    }
+
+    /// Returns the distance of the specified pixel to the nearest edge in the original image.
+    #[inline(always)]
+    pub fn get_distance(&self, x: u32, y: u32) -> f32 {
+        self.distances.get(self.flatten_index(x, y))
+    }
+
+    /// Returns the absolute index of the nearest edge to the specified pixel in the original image.
+    #[inline(always)]
+    pub fn get_distance_target(&self, x: u32, y: u32) -> (u32, u32) {
+        self.distance_targets.get(self.flatten_index(x, y))
+    }
+
+    /// Extracts the lines where the distance is equal to the iso value, using marching squares.
+    /// An iso value of zero extracts the outline of the shape, while positive iso values
+    /// extract the outline grown by that distance, and negative iso values the shrunk outline.
+    pub fn iso_contours(&self, iso_value: f32) -> Vec<Polyline> {
+        crate::iso_contour::iso_contours(self.width, self.height, |x, y| self.get_distance(x, y), iso_value)
+    }
+
+    /// Update the distance and target field at the specified pixel index
+    #[inline(always)]
+    fn set_target_with_distance(&mut self, x: u32, y: u32, target_x: u32, target_y: u32, distance: f32) {
+        let index = self.flatten_index(x, y);
+        self.distances.set(index, distance);
+        self.distance_targets.set(index, (target_x, target_y));
+    }
+
+    /// Update the target field at the specified pixel index and compute the distance
+    #[inline(always)]
+    fn set_target_and_distance(&mut self, x: u32, y: u32, target_x: u32, target_y: u32) -> f32 {
+        let distance = distance(x, y, target_x, target_y);
+        self.set_target_with_distance(x, y, target_x, target_y, distance);
+        distance
+    }
+
+    #[inline(always)]
+    fn take_neighbour_target(
+        &mut self, target_distance: &impl Fn(u32, u32, u32, u32) -> f32,
+        x: u32, y: u32, neighbour_x: i64, neighbour_y: i64, wrap: Wrap
+    ) -> f32 {
+        let (neighbour_x, neighbour_y) = neighbour_position(
+            x, y, neighbour_x, neighbour_y, self.width, self.height, wrap
+        ).expect("neighbour must exist");
+
+        let (target_x, target_y) = self.get_distance_target(neighbour_x, neighbour_y);
+
+        let distance = target_distance(x, y, target_x, target_y);
+        self.set_target_with_distance(x, y, target_x, target_y, distance);
+        distance
+    }
+
+    #[inline(always)]
+    fn invert_distance_sign(&mut self, x: u32, y: u32) {
+        let index = self.flatten_index(x, y);
+        self.distances.set(index, - self.distances.get(index));
+    }
+
+    /// Convert x and y pixel coordinates to the corresponding
+    /// one-dimensional index in a row-major image vector.
+    // Always inline so that the result of self.flatten_index() can be reused in consecutive calls
+    #[inline(always)]
+    pub fn flatten_index(&self, x: u32, y: u32) -> usize {
+        debug_assert!(
+            is_valid_index(x as i64, y as i64, self.width, self.height),
+            "Invalid pixel target index"
+        );
+
+        self.width as usize * y as usize + x as usize
+    }
+
+    /// Scales all distances such that the smallest distance is zero and the largest is one.
+    /// Also computes the former minimum and maximum distance, as well as the new edge-value.
+    /// Returns `None` if the binary image did not contain any shapes.
+    pub fn normalize_distances(self) -> Option<NormalizedDistanceField<D, T>> {
+        NormalizedDistanceField::normalize(self)
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one.
+    /// All distances smaller than `min` and larger than `max` will be clamped.
+    /// Edges (formerly zero-distances) will be at the center, put to `0.5`.
+    /// Also collects the former minimum and maximum distance.
+    /// Returns `None` if the binary image did not contain any shapes.
+    pub fn normalize_clamped_distances(self, min: f32, max: f32) -> Option<NormalizedDistanceField<D, T>> {
+        NormalizedDistanceField::normalize_clamped(self, min, max)
+    }
+
+    /// Scales all distances such that the smallest distance is zero and the largest is one,
+    /// like `normalize_distances`, but reports why the distances could not be normalized.
+    pub fn try_normalize_distances(self) -> Result<NormalizedDistanceField<D, T>> {
+        NormalizedDistanceField::try_normalize(self)
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one,
+    /// like `normalize_clamped_distances`, but reports why the distances could not be normalized.
+    pub fn try_normalize_clamped_distances(self, min: f32, max: f32) -> Result<NormalizedDistanceField<D, T>> {
+        NormalizedDistanceField::try_normalize_clamped(self, min, max)
+    }
+}
+
+/// Returns an error if an image with the specified dimensions has no pixels.
+#[inline]
+pub(crate) fn check_not_empty(width: u32, height: u32) -> Result<()> {
+    if width == 0 || height == 0 { Err(Error::EmptyImage) }
+    else { Ok(()) }
+}
+
+/// Returns if the binary image contains an edge
+/// at the specified pixel compared to the specified neighbour.
+#[inline(always)]
+fn is_at_edge(image: &impl BinaryImage, x: u32, y: u32, neighbour_x: i64, neighbour_y: i64, wrap: Wrap) -> bool {
+    match neighbour_position(x, y, neighbour_x, neighbour_y, image.width(), image.height(), wrap) {
+        // consecutive `image.is_inside(x, y)` should be optimized to a single call in a loop
+        Some((neighbour_x, neighbour_y)) => image.is_inside(x, y) != image.is_inside(neighbour_x, neighbour_y),
+        None => false,
+    }
+}
+
+/// Returns if the binary image contains an edge
+/// at the specified pixel compared to any of its four direct neighbours.
+#[inline(always)]
+fn is_at_any_edge(image: &impl BinaryImage, x: u32, y: u32, wrap: Wrap) -> bool {
+       is_at_edge(image, x, y, -1,  0, wrap)
+    || is_at_edge(image, x, y,  1,  0, wrap)
+    || is_at_edge(image, x, y,  0, -1, wrap)
+    || is_at_edge(image, x, y,  0,  1, wrap)
+}
+
+/// The position of the specified neighbour of a pixel, wrapping around the image borders as specified.
+/// Returns `None` if the neighbour is outside of the image.
+#[inline(always)]
+fn neighbour_position(
+    x: u32, y: u32, neighbour_x: i64, neighbour_y: i64,
+    width: u32, height: u32, wrap: Wrap
+) -> Option<(u32, u32)> {
+    let neighbour_x = wrap_coordinate(x as i64 + neighbour_x, width, wrap.wraps_x())?;
+    let neighbour_y = wrap_coordinate(y as i64 + neighbour_y, height, wrap.wraps_y())?;
+    Some((neighbour_x, neighbour_y))
+}
+
+/// Moves the coordinate into the range `[0, size)` if it wraps,
+/// and returns `None` if it does not wrap and is out of range.
+/// The coordinate must be at most one step outside of the range.
+#[inline(always)]
+fn wrap_coordinate(coordinate: i64, size: u32, wraps: bool) -> Option<u32> {
+    if coordinate >= 0 && coordinate < size as i64 { Some(coordinate as u32) }
+    else if !wraps { None }
+    else if coordinate < 0 { Some((coordinate + size as i64) as u32) }
+    else { Some((coordinate - size as i64) as u32) }
+}
+
+/// The distance between two points on an image whose borders wrap around as specified,
+/// going across the borders if that is shorter.
+#[inline]
+fn wrapped_distance(x: u32, y: u32, target_x: u32, target_y: u32, width: u32, height: u32, wrap: Wrap) -> f32 {
+    let wrapped_difference = |position: u32, target: u32, size: u32, wraps: bool| {
+        let difference = (position as i64 - target as i64).abs();
+        if wraps { difference.min(size as i64 - difference) } else { difference }
+    };
+
+    length(
+        wrapped_difference(x, target_x, width, wrap.wraps_x()),
+        wrapped_difference(y, target_y, height, wrap.wraps_y())
+    )
+}
+
+/// The normalized direction in which the coverage increases the most,
+/// computed from all eight neighbours, pointing inside the shape.
+fn coverage_gradient(image: &impl CoverageImage, x: u32, y: u32) -> (f32, f32) {
+    let coverage = |neighbour_x: i64, neighbour_y: i64| {
+        let neighbour_x = (x as i64 + neighbour_x).clamp(0, image.width() as i64 - 1);
+        let neighbour_y = (y as i64 + neighbour_y).clamp(0, image.height() as i64 - 1);
+        image.coverage(neighbour_x as u32, neighbour_y as u32)
+    };
+
+    let diagonals = std::f32::consts::SQRT_2;
+    let gradient_x = coverage(1, -1) + diagonals * coverage(1, 0) + coverage(1, 1)
+        - coverage(-1, -1) - diagonals * coverage(-1, 0) - coverage(-1, 1);
+
+    let gradient_y = coverage(-1, 1) + diagonals * coverage(0, 1) + coverage(1, 1)
+        - coverage(-1, -1) - diagonals * coverage(0, -1) - coverage(1, -1);
+
+    let length = (gradient_x * gradient_x + gradient_y * gradient_y).sqrt();
+    if length == 0.0 { (0.0, 0.0) } else { (gradient_x / length, gradient_y / length) }
+}
+
+/// Estimates the distance from a pixel center to the edge, in the direction of the gradient,
+/// assuming that the edge is a straight line through the pixel with the specified coverage.
+/// Negative if the pixel center is inside the shape.
+fn edge_distance(gradient_x: f32, gradient_y: f32, coverage: f32) -> f32 {
+    if gradient_x == 0.0 || gradient_y == 0.0 {
+        return 0.5 - coverage;
+    }
+
+    let (major, minor) = {
+        let (x, y) = (gradient_x.abs(), gradient_y.abs());
+        if x < y { (y, x) } else { (x, y) }
+    };
+
+    // the coverage where the edge passes through a corner of the pixel
+    let corner_coverage = 0.5 * minor / major;
+
+    if coverage < corner_coverage {
+        0.5 * (major + minor) - (2.0 * major * minor * coverage).sqrt()
+    }
+    else if coverage < 1.0 - corner_coverage {
+        (0.5 - coverage) * major
+    }
+    else {
+        - 0.5 * (major + minor) + (2.0 * major * minor * (1.0 - coverage)).sqrt()
+    }
+}
+
+/// Calls the function with every pixel of the outer rectangle which is not inside the inner rectangle,
+/// where the inner rectangle must be inside the outer rectangle.
+fn for_each_ring_pixel(outer: Rectangle, inner: Option<Rectangle>, mut function: impl FnMut(u32, u32)) {
+    for y in outer.y .. outer.y + outer.height {
+        match inner {
+            Some(inner) if y >= inner.y && y < inner.y + inner.height => {
+                for x in outer.x .. inner.x { function(x, y); }
+                for x in inner.x + inner.width .. outer.x + outer.width { function(x, y); }
+            },
+
+            _ => for x in outer.x .. outer.x + outer.width { function(x, y); },
+        }
+    }
+}
+
+/// Finds the nearest edge of every pixel in the region, considering only the edges in the searched region,
+/// which must contain the region. Returns a row-major vector with one entry per pixel of the region,
+/// which is `None` if the searched region does not contain any edges.
+fn nearest_edges_within(binary_image: &impl BinaryImage, region: Rectangle, searched: Rectangle) -> Vec<Option<(u32, u32)>> {
+    let columns = searched.x .. searched.x + searched.width;
+    let column_index = |x: u32, y: u32| (y - searched.y) as usize * searched.width as usize + (x - searched.x) as usize;
+
+    // for every pixel, find the nearest edge above it in the same column
+    let mut column_edges: Vec<Option<u32>> = vec![None; searched.width as usize * searched.height as usize];
+    for y in searched.y .. searched.y + searched.height {
+        for x in columns.clone() {
+            column_edges[column_index(x, y)] = if is_at_any_edge(binary_image, x, y, Wrap::None) { Some(y) }
+                else if y > searched.y { column_edges[column_index(x, y - 1)] }
+                else { None };
         }
+    }
 
-        // perform backwards iteration
-        for y in (0..height).rev() {
-            for x in (0..width).rev() {
-                // encourage auto vectorization and fetching all distances in parallel
-                let right    = distance_field.distance_by_neighbour(x, y,  1,  0);
-                let top_left = distance_field.distance_by_neighbour(x, y, -1,  1);
-                let top      = distance_field.distance_by_neighbour(x, y,  0,  1);
-                let top_right= distance_field.distance_by_neighbour(x, y,  1,  1);
-                let mut own  = distance_field.get_distance(x, y);
-
-                // if any of the neighbour is smaller, update ourselves
-                // TODO only write the true smallest instead of overwriting previous distances?
-                if right     < own { own = distance_field.take_neighbour_target(x, y,  1,  0); }
-                if top_left  < own { own = distance_field.take_neighbour_target(x, y, -1,  1); }
-                if top       < own { own = distance_field.take_neighbour_target(x, y,  0,  1); }
-                if top_right < own {       distance_field.take_neighbour_target(x, y,  1,  1); }
+    // replace it with the nearest edge below, if that one is closer,
+    // where the next row only knows of an edge below if it is not closer to an edge above
+    for y in (searched.y .. searched.y + searched.height - 1).rev() {
+        for x in columns.clone() {
+            if let Some(below) = column_edges[column_index(x, y + 1)].filter(|&below| below > y) {
+                let is_closer = column_edges[column_index(x, y)]
+                    .map_or(true, |above| below - y < y - above);
+
+                if is_closer { column_edges[column_index(x, y)] = Some(below); }
             }
         }
+    }
 
-        // flip distance signs
-        // where a pixel is inside the shape
-        for y in 0..height {
-            for x in 0..width {
-                if binary_image.is_inside(x, y) {
-                    distance_field.invert_distance_sign(x, y);
-                }
+    // for every row of the region, find the nearest of all the column edges
+    let mut nearest_edges = vec![None; region.width as usize * region.height as usize];
+    let mut envelope = EdgeEnvelope::with_capacity(searched.width as usize);
+
+    for y in region.y .. region.y + region.height {
+        envelope.clear();
+
+        for column in columns.clone() {
+            if let Some(edge_y) = column_edges[column_index(column, y)] {
+                envelope.push(column, edge_y, y);
             }
         }
 
-        distance_field
+        envelope.for_each_nearest_edge(region.x .. region.x + region.width, |x, edge| {
+            nearest_edges[(y - region.y) as usize * region.width as usize + (x - region.x) as usize] = Some(edge);
+        });
     }
 
-    /// Returns a potentially smaller distance, based on the neighbour's distance.
-    /// If there is no neighbour (at the bounds of the image), `INFINITY` is returned.
-    #[inline(always)]
-    fn distance_by_neighbour(&mut self, x: u16, y: u16, neighbour_x: i32, neighbour_y: i32, ) -> f32 {
-        // this should be const per function call, as `neighbour` is const per function call
-        let distance_to_neighbour = length(neighbour_x, neighbour_y);
-        let neighbour_x = x as i32 + neighbour_x;
-        let neighbour_y = y as i32 + neighbour_y;
-
-        // if neighbour exists, return the potentially smaller distance to the target
-        if is_valid_index(neighbour_x, neighbour_y, self.width, self.height) {
-            let neighbours_distance = self.get_distance(
-                neighbour_x as u16, neighbour_y as u16
-            );
+    nearest_edges
+}
 
-            neighbours_distance + distance_to_neighbour
+/// Finds the nearest edge in the same column for every pixel of the specified column,
+/// storing the row of that edge, or `None` if the column does not contain any edge.
+/// This is the first pass of the algorithm by Felzenszwalb and Huttenlocher.
+fn nearest_column_edges(column: &mut [Option<u32>], is_edge: impl Fn(u32) -> bool) {
+    // for every pixel, find the nearest edge above it
+    let mut nearest_edge_y = None;
+    for (y, edge_y) in column.iter_mut().enumerate() {
+        if is_edge(y as u32) {
+            nearest_edge_y = Some(y as u32);
         }
 
-        else {
-            std::f32::INFINITY
-        }
+        *edge_y = nearest_edge_y;
     }
 
-    /// Returns the distance of the specified pixel to the nearest edge in the original image.
-    #[inline(always)]
-    pub fn get_distance(&self, x: u16, y: u16) -> f32 {
-        self.distances.get(self.flatten_index(x, y))
-    }
+    // replace it with the nearest edge below, if that one is closer
+    nearest_edge_y = None;
+    for (y, edge_y) in column.iter_mut().enumerate().rev() {
+        let y = y as u32;
 
-    /// Returns the absolute index of the nearest edge to the specified pixel in the original image.
-    #[inline(always)]
-    pub fn get_distance_target(&self, x: u16, y: u16) -> (u16, u16) {
-        self.distance_targets[self.flatten_index(x, y)]
-    }
+        if *edge_y == Some(y) {
+            nearest_edge_y = Some(y);
+        }
 
-    /// Update the distance and target field at the specified pixel index
-    #[inline(always)]
-    fn set_target_with_distance(&mut self, x: u16, y: u16, target_x: u16, target_y: u16, distance: f32) {
-        let index = self.flatten_index(x, y);
-        self.distances.set(index, distance);
-        self.distance_targets[index] = (target_x, target_y);
+        else if let Some(below) = nearest_edge_y {
+            if edge_y.map_or(true, |above| below - y < y - above) {
+                *edge_y = Some(below);
+            }
+        }
     }
+}
 
-    /// Update the target field at the specified pixel index and compute the distance
-    #[inline(always)]
-    fn set_target_and_distance(&mut self, x: u16, y: u16, target_x: u16, target_y: u16) -> f32 {
-        let distance = distance(x, y, target_x, target_y);
-        self.set_target_with_distance(x, y, target_x, target_y, distance);
-        distance
+/// Finds the nearest of all column edges for every pixel of the specified row,
+/// where the column edges are stored column by column, as found by `nearest_column_edges`.
+/// Calls the function with each pixel of the row which has any edge.
+/// This is the second pass of the algorithm by Felzenszwalb and Huttenlocher.
+fn nearest_row_edges(
+    envelope: &mut EdgeEnvelope, column_edges: &[Option<u32>], width: u32, height: u32, y: u32,
+    function: impl FnMut(u32, (u32, u32))
+) {
+    envelope.clear();
+
+    for column in 0..width {
+        if let Some(edge_y) = column_edges[column as usize * height as usize + y as usize] {
+            envelope.push(column, edge_y, y);
+        }
     }
 
-    #[inline(always)]
-    fn take_neighbour_target(&mut self, x: u16, y: u16, neighbour_x: i32, neighbour_y: i32) -> f32 {
-        debug_assert!(x as i32 + neighbour_x >= 0 && y as i32 + neighbour_y >= 0);
-        let target_of_neighbour = self.get_distance_target(
-            (x as i32 + neighbour_x) as u16,
-            (y as i32 + neighbour_y) as u16
-        );
+    envelope.for_each_nearest_edge(0..width, function);
+}
+
+/// The lower envelope of the parabolas `(x - column)^2 + (y - edge_y)^2`
+/// of all column edges in a single row, used to find the nearest edge for each pixel in that row.
+#[derive(Clone, Debug, Default)]
+struct EdgeEnvelope {
+    /// The column, the edge row and the squared vertical edge distance of each parabola.
+    parabolas: Vec<(u32, u32, f64)>,
+
+    /// The horizontal position where each parabola starts being the lowest.
+    boundaries: Vec<f64>,
+}
 
-        self.set_target_and_distance(x, y, target_of_neighbour.0, target_of_neighbour.1)
+impl EdgeEnvelope {
+    fn with_capacity(width: usize) -> Self {
+        EdgeEnvelope {
+            parabolas: Vec::with_capacity(width),
+            boundaries: Vec::with_capacity(width),
+        }
     }
 
-    #[inline(always)]
-    fn invert_distance_sign(&mut self, x: u16, y: u16) {
-        let index = self.flatten_index(x, y);
-        self.distances.set(index, - self.distances.get(index));
+    fn clear(&mut self) {
+        self.parabolas.clear();
+        self.boundaries.clear();
     }
 
-    /// Convert x and y pixel coordinates to the corresponding
-    /// one-dimensional index in a row-major image vector.
-    // Always inline so that the result of self.flatten_index() can be reused in consecutive calls
-    #[inline(always)]
-    pub fn flatten_index(&self, x: u16, y: u16) -> usize {
-        debug_assert!(
-            is_valid_index(x as i32, y as i32, self.width, self.height),
-            "Invalid pixel target index"
-        );
+    /// Add the nearest edge of the next column, which must be right of all previous columns.
+    fn push(&mut self, column: u32, edge_y: u32, y: u32) {
+        let squared_height = (edge_y as f64 - y as f64).powi(2);
+        let mut boundary = f64::NEG_INFINITY;
+
+        while let (Some(&(previous, _, previous_height)), Some(&previous_boundary))
+            = (self.parabolas.last(), self.boundaries.last())
+        {
+            let intersection = parabola_intersection(previous, previous_height, column, squared_height);
+            if intersection > previous_boundary {
+                boundary = intersection;
+                break;
+            }
 
-        self.width as usize * y as usize + x as usize
-    }
+            self.parabolas.pop();
+            self.boundaries.pop();
+        }
 
-    /// Scales all distances such that the smallest distance is zero and the largest is one.
-    /// Also computes the former minimum and maximum distance, as well as the new edge-value.
-    /// Returns `None` if the binary image did not contain any shapes.
-    pub fn normalize_distances(self) -> Option<NormalizedDistanceField<D>> {
-        NormalizedDistanceField::normalize(self)
+        self.parabolas.push((column, edge_y, squared_height));
+        self.boundaries.push(boundary);
     }
 
-    /// Scales all distances such that the `min` distances are zero and `max` distances are one.
-    /// All distances smaller than `min` and larger than `max` will be clamped.
-    /// Edges (formerly zero-distances) will be at the center, put to `0.5`.
-    /// Also collects the former minimum and maximum distance.
-    /// Returns `None` if the binary image did not contain any shapes.
-    pub fn normalize_clamped_distances(self, min: f32, max: f32) -> Option<NormalizedDistanceField<D>> {
-        NormalizedDistanceField::normalize_clamped(self, min, max)
-    }
-}
+    /// Calls the function with the nearest edge of each pixel of the specified columns in the row.
+    /// Does nothing if no edges have been added.
+    fn for_each_nearest_edge(&self, columns: Range<u32>, mut function: impl FnMut(u32, (u32, u32))) {
+        if self.parabolas.is_empty() { return; }
 
-/// Returns if the binary image contains an edge
-/// at the specified pixel compared to the specified neighbour.
-#[inline(always)]
-fn is_at_edge(image: &impl BinaryImage, x: u16, y: u16, neighbour_x: i32, neighbour_y: i32) -> bool {
-    let neighbour_x = x as i32 + neighbour_x;
-    let neighbour_y = y as i32 + neighbour_y;
+        let mut parabola = 0;
+        for x in columns {
+            while parabola + 1 < self.parabolas.len() && self.boundaries[parabola + 1] < x as f64 {
+                parabola += 1;
+            }
 
-    is_valid_index(neighbour_x, neighbour_y, image.width(), image.height())
+            let (edge_x, edge_y, _) = self.parabolas[parabola];
+            function(x, (edge_x, edge_y));
+        }
+    }
+}
 
-        // consecutive `image.is_inside(x, y)` should be optimized to a single call in a loop
-        && image.is_inside(x, y) != image.is_inside(neighbour_x as u16, neighbour_y as u16)
+/// The horizontal position where two parabolas with the specified
+/// vertex columns and squared heights intersect.
+#[inline]
+fn parabola_intersection(left: u32, left_height: f64, right: u32, right_height: f64) -> f64 {
+    let left = left as f64;
+    let right = right as f64;
+    ((right_height + right * right) - (left_height + left * left)) / (2.0 * (right - left))
 }
 
 /// The length of a vector with x and y coordinates.
 #[inline]
-fn length(x: i32, y: i32) -> f32 {
+fn length(x: i64, y: i64) -> f32 {
     ((x * x + y * y) as f32).sqrt()
 }
 
 /// The distance between to points with x and y coordinates.
 #[inline]
-fn distance(x: u16, y: u16, target_x: u16, target_y: u16) -> f32 {
-    length(x as i32 - target_x as i32, y as i32 - target_y as i32)
+fn distance(x: u32, y: u32, target_x: u32, target_y: u32) -> f32 {
+    length(x as i64 - target_x as i64, y as i64 - target_y as i64)
 }
 
 /// Check if x and y are valid pixel coordinates
 /// inside an image with the specified width and height.
 #[inline]
-fn is_valid_index(x: i32, y: i32, width: u16, height: u16) -> bool {
-    x >= 0 && y >= 0 && x < width as i32 && y < height as i32
+fn is_valid_index(x: i64, y: i64, width: u32, height: u32) -> bool {
+    x >= 0 && y >= 0 && x < width as i64 && y < height as i64
 }
 
 /// Scale the value so that it fits into the range `[0,1]`.
@@ -305,24 +1511,36 @@ fn normalize(value: f32, min: f32, max: f32) -> f32 {
 }
 
 
-impl<D> NormalizedDistanceField<D> where D: DistanceStorage {
+impl<D, T> NormalizedDistanceField<D, T> where D: DistanceStorage, T: TargetStorage {
 
     /// Scales all distances such that the smallest distance is zero and the largest is one.
     /// Also computes the former minimum and maximum distance, as well as the new edge-value.
     /// Returns `None` if the binary image did not contain any shapes.
-    pub fn normalize(distance_field: SignedDistanceField<D>) -> Option<Self> {
+    pub fn normalize(distance_field: SignedDistanceField<D, T>) -> Option<Self> {
+        Self::try_normalize(distance_field).ok()
+    }
+
+    /// Scales all distances such that the smallest distance is zero and the largest is one.
+    /// Also computes the former minimum and maximum distance, as well as the new edge-value.
+    /// Returns an error if the binary image did not contain any shapes,
+    /// or if all distances are equal.
+    pub fn try_normalize(distance_field: SignedDistanceField<D, T>) -> Result<Self> {
         let mut distance_field = distance_field;
         let width = distance_field.width;
         let height = distance_field.height;
 
         let (min, max) = (0..width as usize * height as usize)
             .map(|index| distance_field.distances.get(index))
-            .fold((std::f32::INFINITY, std::f32::NEG_INFINITY), |(min, max), distance|
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), distance|
                 (min.min(distance), max.max(distance))
             );
 
         if min.is_infinite() || max.is_infinite() {
-            return None;
+            return Err(Error::NoShapeFound);
+        }
+
+        if min == max {
+            return Err(Error::ZeroSizeRange);
         }
 
         for index in 0..width as usize * height as usize {
@@ -331,7 +1549,7 @@ impl<D> NormalizedDistanceField<D> where D: DistanceStorage {
             distance_field.distances.set(index, normalized);
         }
 
-        Some(NormalizedDistanceField {
+        Ok(NormalizedDistanceField {
             width, height,
             distances: distance_field.distances,
             zero_distance: normalize(0.0, min, max),
@@ -347,20 +1565,32 @@ impl<D> NormalizedDistanceField<D> where D: DistanceStorage {
     ///
     /// Also collects the former minimum and maximum distance.
     /// Returns `None` if the binary image did not contain any shapes.
-    pub fn normalize_clamped(distance_field: SignedDistanceField<D>, min: f32, max: f32) -> Option<Self> {
+    pub fn normalize_clamped(distance_field: SignedDistanceField<D, T>, min: f32, max: f32) -> Option<Self> {
+        Self::try_normalize_clamped(distance_field, min, max).ok()
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one,
+    /// like `normalize_clamped`.
+    /// Returns an error if the binary image did not contain any shapes,
+    /// or if `min` and `max` are equal.
+    pub fn try_normalize_clamped(distance_field: SignedDistanceField<D, T>, min: f32, max: f32) -> Result<Self> {
+        if min == max {
+            return Err(Error::ZeroSizeRange);
+        }
+
         let mut normalized = NormalizedDistanceField {
             width: distance_field.width,
             height: distance_field.height,
             distances: distance_field.distances,
-            former_min_distance: std::f32::INFINITY,
-            former_max_distance: std::f32::NEG_INFINITY,
+            former_min_distance: f32::INFINITY,
+            former_max_distance: f32::NEG_INFINITY,
             zero_distance: normalize(0.0, min, max), // TODO untested
             distance_targets: distance_field.distance_targets
         };
 
         for index in 0..normalized.width as usize * normalized.height as usize {
             let distance = normalized.distances.get(index);
-            if distance.is_infinite() { return None; }
+            if distance.is_infinite() { return Err(Error::NoShapeFound); }
 
             normalized.former_max_distance = normalized.former_max_distance.max(distance);
             normalized.former_min_distance = normalized.former_min_distance.min(distance);
@@ -370,34 +1600,39 @@ impl<D> NormalizedDistanceField<D> where D: DistanceStorage {
             normalized.distances.set(index, normalized_distance);
         }
 
-        Some(normalized)
+        Ok(normalized)
     }
 
     /// Convert the normalized distance to an `u8` image with the range fully utilized.
     pub fn to_u8(&self) -> Vec<u8> {
         (0..self.width as usize * self.height as usize)
-            .map(|index| (self.distances.get(index).min(1.0).max(0.0) * std::u8::MAX as f32) as u8)
+            .map(|index| (self.distances.get(index).clamp(0.0, 1.0) * u8::MAX as f32) as u8)
             .collect()
     }
 
     /// Convert the normalized distance to an `u16` image with the range fully utilized.
     pub fn to_u16(&self) -> Vec<u16> {
         (0..self.width as usize * self.height as usize)
-            .map(|index| (self.distances.get(index).min(1.0).max(0.0) * std::u16::MAX as f32) as u16)
+            .map(|index| (self.distances.get(index).clamp(0.0, 1.0) * u16::MAX as f32) as u16)
             .collect()
     }
 
     /// Convert the normalized distance to an `u8` gray piston image with the range fully utilized.
     #[cfg(feature = "piston_image")]
     pub fn to_gray_u8_image(&self) -> image::GrayImage {
-        image::GrayImage::from_raw(self.width as u32, self.height as u32, self.to_u8())
+        image::GrayImage::from_raw(self.width, self.height, self.to_u8())
             .expect("incorrect vector length")
     }
 }
 
 impl DistanceStorage for F16DistanceStorage {
     fn new(length: usize) -> Self {
-        vec![half::consts::INFINITY; length]
+        vec![half::f16::INFINITY; length]
+    }
+
+    fn reset(&mut self, length: usize) {
+        self.clear();
+        self.resize(length, half::f16::INFINITY);
     }
 
     #[inline(always)]
@@ -413,7 +1648,12 @@ impl DistanceStorage for F16DistanceStorage {
 
 impl DistanceStorage for F32DistanceStorage {
     fn new(length: usize) -> Self {
-        vec![std::f32::INFINITY; length]
+        vec![f32::INFINITY; length]
+    }
+
+    fn reset(&mut self, length: usize) {
+        self.clear();
+        self.resize(length, f32::INFINITY);
     }
 
     #[inline(always)]
@@ -425,4 +1665,337 @@ impl DistanceStorage for F32DistanceStorage {
     fn set(&mut self, index: usize, distance: f32) {
         self[index] = distance
     }
-}
\ No newline at end of file
+}
+
+impl<const RANGE: u32> DistanceStorage for U8DistanceStorage<RANGE> {
+    fn new(length: usize) -> Self {
+        U8DistanceStorage { values: vec![u8::MAX; length] }
+    }
+
+    fn reset(&mut self, length: usize) {
+        self.values.clear();
+        self.values.resize(length, u8::MAX);
+    }
+
+    #[inline(always)]
+    fn get(&self, index: usize) -> f32 {
+        dequantize(self.values[index] as i64, 128, u8::MIN as i64, u8::MAX as i64, RANGE as f32 / 126.0)
+    }
+
+    #[inline(always)]
+    fn set(&mut self, index: usize, distance: f32) {
+        self.values[index] = Self::quantized(distance)
+    }
+}
+
+impl<const RANGE: u32> U8DistanceStorage<RANGE> {
+    #[inline(always)]
+    fn quantized(distance: f32) -> u8 {
+        quantize(distance, 128, u8::MIN as i64, u8::MAX as i64, RANGE as f32 / 126.0) as u8
+    }
+}
+
+impl<const RANGE: u32> DistanceStorage for U16DistanceStorage<RANGE> {
+    fn new(length: usize) -> Self {
+        U16DistanceStorage { values: vec![u16::MAX; length] }
+    }
+
+    fn reset(&mut self, length: usize) {
+        self.values.clear();
+        self.values.resize(length, u16::MAX);
+    }
+
+    #[inline(always)]
+    fn get(&self, index: usize) -> f32 {
+        dequantize(self.values[index] as i64, 32768, u16::MIN as i64, u16::MAX as i64, RANGE as f32 / 32766.0)
+    }
+
+    #[inline(always)]
+    fn set(&mut self, index: usize, distance: f32) {
+        self.values[index] = Self::quantized(distance)
+    }
+}
+
+impl<const RANGE: u32> U16DistanceStorage<RANGE> {
+    #[inline(always)]
+    fn quantized(distance: f32) -> u16 {
+        quantize(distance, 32768, u16::MIN as i64, u16::MAX as i64, RANGE as f32 / 32766.0) as u16
+    }
+}
+
+impl<const SCALE: u32> DistanceStorage for I16DistanceStorage<SCALE> {
+    fn new(length: usize) -> Self {
+        I16DistanceStorage { values: vec![i16::MAX; length] }
+    }
+
+    fn reset(&mut self, length: usize) {
+        self.values.clear();
+        self.values.resize(length, i16::MAX);
+    }
+
+    #[inline(always)]
+    fn get(&self, index: usize) -> f32 {
+        dequantize(self.values[index] as i64, 0, i16::MIN as i64, i16::MAX as i64, 1.0 / SCALE as f32)
+    }
+
+    #[inline(always)]
+    fn set(&mut self, index: usize, distance: f32) {
+        self.values[index] = Self::quantized(distance)
+    }
+}
+
+impl<const SCALE: u32> I16DistanceStorage<SCALE> {
+    #[inline(always)]
+    fn quantized(distance: f32) -> i16 {
+        quantize(distance, 0, i16::MIN as i64, i16::MAX as i64, 1.0 / SCALE as f32) as i16
+    }
+}
+
+/// Convert a distance to the nearest of the integer steps around the zero value,
+/// saturating to the minimum and maximum value, which represent infinite distances.
+/// The same number of finite steps is available on both sides of the zero value,
+/// so that a distance and its negation are quantized symmetrically.
+#[inline(always)]
+fn quantize(distance: f32, zero: i64, min: i64, max: i64, step: f32) -> i64 {
+    if distance.is_nan() { return max; }
+
+    let limit = (zero - min).min(max - zero) - 1;
+    let steps = (distance / step).round();
+
+    if steps > limit as f32 { max }
+    else if steps < -limit as f32 { min }
+    else { zero + steps as i64 }
+}
+
+/// Convert an integer step around the zero value to a distance,
+/// where the minimum and maximum value represent infinite distances.
+#[inline(always)]
+fn dequantize(value: i64, zero: i64, min: i64, max: i64, step: f32) -> f32 {
+    if value == max { f32::INFINITY }
+    else if value == min { f32::NEG_INFINITY }
+    else { (value - zero) as f32 * step }
+}
+
+impl TargetStorage for U16TargetStorage {
+    fn new(length: usize) -> Self {
+        vec![(0, 0); length]
+    }
+
+    fn reset(&mut self, length: usize) {
+        self.clear();
+        self.resize(length, (0, 0));
+    }
+
+    fn max_side() -> u32 {
+        u16::MAX as u32 + 1
+    }
+
+    #[inline(always)]
+    fn get(&self, index: usize) -> (u32, u32) {
+        let (x, y) = self[index];
+        (x as u32, y as u32)
+    }
+
+    #[inline(always)]
+    fn set(&mut self, index: usize, (x, y): (u32, u32)) {
+        self[index] = (x as u16, y as u16)
+    }
+}
+
+impl TargetStorage for U32TargetStorage {
+    fn new(length: usize) -> Self {
+        vec![(0, 0); length]
+    }
+
+    fn reset(&mut self, length: usize) {
+        self.clear();
+        self.resize(length, (0, 0));
+    }
+
+    fn max_side() -> u32 {
+        u32::MAX
+    }
+
+    #[inline(always)]
+    fn get(&self, index: usize) -> (u32, u32) {
+        self[index]
+    }
+
+    #[inline(always)]
+    fn set(&mut self, index: usize, target: (u32, u32)) {
+        self[index] = target
+    }
+}
+
+impl Wrap {
+    /// Whether the left and right borders wrap around.
+    #[inline]
+    pub fn wraps_x(self) -> bool {
+        self == Wrap::Horizontal || self == Wrap::Both
+    }
+
+    /// Whether the top and bottom borders wrap around.
+    #[inline]
+    pub fn wraps_y(self) -> bool {
+        self == Wrap::Vertical || self == Wrap::Both
+    }
+}
+
+impl Rectangle {
+
+    /// Create a rectangle with the specified top left pixel and size.
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Rectangle { x, y, width, height }
+    }
+
+    /// Whether this rectangle does not contain any pixels.
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Whether the pixel is inside of this rectangle.
+    #[inline]
+    pub fn contains(self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y
+            && (x - self.x) < self.width && (y - self.y) < self.height
+    }
+
+    /// The part of this rectangle which is inside an image of the specified size.
+    fn clamped(self, width: u32, height: u32) -> Self {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+        let right = self.x.saturating_add(self.width).min(width);
+        let bottom = self.y.saturating_add(self.height).min(height);
+        Rectangle::new(x, y, right - x, bottom - y)
+    }
+
+    /// The smallest rectangle which contains both this rectangle and the pixel.
+    fn including(self, x: u32, y: u32) -> Self {
+        let left = self.x.min(x);
+        let top = self.y.min(y);
+        let right = (self.x + self.width).max(x + 1);
+        let bottom = (self.y + self.height).max(y + 1);
+        Rectangle::new(left, top, right - left, bottom - top)
+    }
+
+    /// This rectangle, extended by the margin on all sides,
+    /// but not beyond the bounds of an image of the specified size.
+    fn grown(self, margin: u32, width: u32, height: u32) -> Self {
+        let x = self.x.saturating_sub(margin);
+        let y = self.y.saturating_sub(margin);
+        let right = (self.x + self.width).saturating_add(margin).min(width);
+        let bottom = (self.y + self.height).saturating_add(margin).min(height);
+        Rectangle::new(x, y, right - x, bottom - y)
+    }
+
+    /// The distance from the pixel to the nearest pixel of this rectangle, zero if the pixel is inside.
+    fn distance_to(self, x: u32, y: u32) -> f32 {
+        let distance_x = (self.x as i64 - x as i64).max(x as i64 - (self.x + self.width - 1) as i64).max(0);
+        let distance_y = (self.y as i64 - y as i64).max(y as i64 - (self.y + self.height - 1) as i64).max(0);
+        length(distance_x, distance_y)
+    }
+
+    /// A lower bound for the distance from the pixel inside this rectangle
+    /// to any pixel in an image of the specified size, which is outside of this rectangle.
+    /// Is `INFINITY` if this rectangle covers the whole image.
+    fn distance_to_outside(self, x: u32, y: u32, width: u32, height: u32) -> f32 {
+        let mut distance = f32::INFINITY;
+        if self.x > 0 { distance = distance.min((x - self.x + 1) as f32); }
+        if self.y > 0 { distance = distance.min((y - self.y + 1) as f32); }
+        if self.x + self.width < width { distance = distance.min((self.x + self.width - x) as f32); }
+        if self.y + self.height < height { distance = distance.min((self.y + self.height - y) as f32); }
+        distance
+    }
+}
+
+impl<T> TargetStorage for DiscardedTargetStorage<T> where T: TargetStorage {
+    fn new(length: usize) -> Self {
+        DiscardedTargetStorage { targets: T::new(length), is_discarded: false }
+    }
+
+    fn reset(&mut self, length: usize) {
+        self.targets.reset(length);
+        self.is_discarded = false;
+    }
+
+    fn max_side() -> u32 {
+        T::max_side()
+    }
+
+    fn finish(&mut self) {
+        self.targets.reset(0);
+        self.is_discarded = true;
+    }
+
+    fn release(&mut self) {
+        if self.is_discarded { self.targets = T::new(0); }
+    }
+
+    #[inline(always)]
+    fn get(&self, index: usize) -> (u32, u32) {
+        assert!(!self.is_discarded, "Distance targets have been discarded");
+        self.targets.get(index)
+    }
+
+    #[inline(always)]
+    fn set(&mut self, index: usize, target: (u32, u32)) {
+        self.targets.set(index, target)
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl ParallelDistanceStorage for F16DistanceStorage {
+    type Value = half::f16;
+    fn values_mut(&mut self) -> &mut [half::f16] { self }
+    fn value_of(distance: f32) -> half::f16 { half::f16::from_f32(distance) }
+}
+
+#[cfg(feature = "parallel")]
+impl ParallelDistanceStorage for F32DistanceStorage {
+    type Value = f32;
+    fn values_mut(&mut self) -> &mut [f32] { self }
+    fn value_of(distance: f32) -> f32 { distance }
+}
+
+#[cfg(feature = "parallel")]
+impl<const RANGE: u32> ParallelDistanceStorage for U8DistanceStorage<RANGE> {
+    type Value = u8;
+    fn values_mut(&mut self) -> &mut [u8] { &mut self.values }
+    fn value_of(distance: f32) -> u8 { Self::quantized(distance) }
+}
+
+#[cfg(feature = "parallel")]
+impl<const RANGE: u32> ParallelDistanceStorage for U16DistanceStorage<RANGE> {
+    type Value = u16;
+    fn values_mut(&mut self) -> &mut [u16] { &mut self.values }
+    fn value_of(distance: f32) -> u16 { Self::quantized(distance) }
+}
+
+#[cfg(feature = "parallel")]
+impl<const SCALE: u32> ParallelDistanceStorage for I16DistanceStorage<SCALE> {
+    type Value = i16;
+    fn values_mut(&mut self) -> &mut [i16] { &mut self.values }
+    fn value_of(distance: f32) -> i16 { Self::quantized(distance) }
+}
+
+#[cfg(feature = "parallel")]
+impl ParallelTargetStorage for U16TargetStorage {
+    type Value = (u16, u16);
+    fn values_mut(&mut self) -> &mut [(u16, u16)] { self }
+    fn value_of((x, y): (u32, u32)) -> (u16, u16) { (x as u16, y as u16) }
+}
+
+#[cfg(feature = "parallel")]
+impl ParallelTargetStorage for U32TargetStorage {
+    type Value = (u32, u32);
+    fn values_mut(&mut self) -> &mut [(u32, u32)] { self }
+    fn value_of(target: (u32, u32)) -> (u32, u32) { target }
+}
+
+#[cfg(feature = "parallel")]
+impl<T> ParallelTargetStorage for DiscardedTargetStorage<T> where T: ParallelTargetStorage {
+    type Value = T::Value;
+    fn values_mut(&mut self) -> &mut [T::Value] { self.targets.values_mut() }
+    fn value_of(target: (u32, u32)) -> T::Value { T::value_of(target) }
+}
@@ -0,0 +1,650 @@
+use crate::binary_volume::BinaryVolume;
+use crate::distance_field::DistanceStorage;
+use crate::error::{ Error, Result };
+
+/// Contains the distance volume and the vector volume produced by `SignedDistanceVolume::compute`.
+/// Can be normalized in order to convert to a volume with limited range.
+/// The type parameter `D` can be used to customize the memory layout of the distance volume,
+/// just like for a `SignedDistanceField`.
+/// The type parameter `T` specifies how to store the distance targets.
+/// By default, `u16` coordinates are used, which supports volumes with up to 65536 voxels per side.
+///
+/// If any distance in this volume is `INFINITY`, no shapes were found in the binary volume.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SignedDistanceVolume<D: DistanceStorage, T: VolumeTargetStorage = U16VolumeTargetStorage> {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+
+    /// One row-major image vector after another with
+    /// for each voxel of the original volume
+    /// containing the distance from that voxel to the nearest edge
+    pub distances: D,
+
+    /// One row-major image vector after another with
+    /// for each voxel of the original volume
+    /// containing the absolute position of the nearest edge from that voxel
+    pub distance_targets: T
+}
+
+/// Represents a distance volume which was normalized to the range `[0, 1]`.
+/// Also contains information about the greatest distances of the unnormalized distance volume.
+pub struct NormalizedDistanceVolume<D: DistanceStorage, T: VolumeTargetStorage = U16VolumeTargetStorage> {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+
+    /// All distances are in the range of `[0..1]`.
+    pub distances: D,
+
+    /// In the original distance volume, edges are represented by a distance of zero.
+    /// Normalizing the distance volume will result in edges no longer being zero.
+    /// The normalized volume will have edges somewhere between zero and one.
+    /// This float describes the new value that edges in the normalized volume have.
+    pub zero_distance: f32,
+
+    /// The largest distance in the volume
+    /// to the nearest edge
+    /// __outside__ of a shape .
+    pub former_max_distance: f32,
+
+    /// The largest distance in the volume
+    /// to the nearest edge
+    /// __inside__ of a shape
+    pub former_min_distance: f32,
+
+    /// One row-major image vector after another with
+    /// for each voxel of the original volume
+    /// containing the absolute position of the nearest edge from that voxel
+    pub distance_targets: T
+}
+
+/// Store volume distance targets as a vector of `u16` coordinates.
+/// Needs less storage, but supports only volumes with up to 65536 voxels per side.
+pub type U16VolumeTargetStorage = Vec<(u16, u16, u16)>;
+
+/// Store volume distance targets as a vector of `u32` coordinates.
+/// Needs twice the storage, but supports volumes of any size.
+pub type U32VolumeTargetStorage = Vec<(u32, u32, u32)>;
+
+/// Specifies how to store the distance targets of a volume in memory,
+/// like `TargetStorage` does for distance fields.
+/// This library defines an `u16` storage and an `u32` storage.
+pub trait VolumeTargetStorage {
+
+    /// Construct a new linear storage with the specified length.
+    /// All targets in this array should be initialized to `(0, 0, 0)`.
+    fn new(length: usize) -> Self;
+
+    /// The largest volume width, height and depth whose voxel coordinates fit into this storage.
+    fn max_side() -> u32;
+
+    fn get(&self, index: usize) -> (u32, u32, u32);
+
+    fn set(&mut self, index: usize, target: (u32, u32, u32));
+}
+
+/// The neighbours which have already been visited when scanning forwards,
+/// that is, all voxels in the previous slice, in the previous row, and the previous voxel.
+/// The backward pass uses the mirrored neighbours.
+const FORWARD_NEIGHBOURS: [(i64, i64, i64); 13] = [
+    (-1, -1, -1), ( 0, -1, -1), ( 1, -1, -1),
+    (-1,  0, -1), ( 0,  0, -1), ( 1,  0, -1),
+    (-1,  1, -1), ( 0,  1, -1), ( 1,  1, -1),
+    (-1, -1,  0), ( 0, -1,  0), ( 1, -1,  0),
+    (-1,  0,  0),
+];
+
+
+impl<D, T> SignedDistanceVolume<D, T> where D: DistanceStorage, T: VolumeTargetStorage {
+
+    /// Create a distance volume with all distances being `INFINITY`.
+    /// Panics if the target storage cannot store coordinates of this size.
+    fn new(width: u32, height: u32, depth: u32) -> Self {
+        let max_side = T::max_side();
+        assert!(
+            width <= max_side && height <= max_side && depth <= max_side,
+            "Volume too large for target storage"
+        );
+
+        let length = width as usize * height as usize * depth as usize;
+        SignedDistanceVolume {
+            width, height, depth,
+            distances: D::new(length),
+            distance_targets: T::new(length),
+        }
+    }
+
+    /// Approximates the signed distance volume of the specified binary volume.
+    /// The algorithm used is the dead reckoning signed distance transform
+    /// by George J. Grevara, 2004, extended to three dimensions
+    /// by considering all 26 neighbours of each voxel.
+    pub fn compute(binary_volume: &impl BinaryVolume) -> Self {
+        let width = binary_volume.width();
+        let height = binary_volume.height();
+        let depth = binary_volume.depth();
+
+        let mut distance_volume = Self::new(width, height, depth);
+
+        // for every voxel directly at an edge, set its distance to zero
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    if is_at_any_edge(binary_volume, x, y, z) {
+                        distance_volume.set_target_with_distance(x, y, z, (x, y, z), 0.0);
+                    }
+                }
+            }
+        }
+
+        // perform forwards iteration
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    for &(neighbour_x, neighbour_y, neighbour_z) in &FORWARD_NEIGHBOURS {
+                        distance_volume.take_nearer_neighbour_target(x, y, z, neighbour_x, neighbour_y, neighbour_z);
+                    }
+                }
+            }
+        }
+
+        // perform backwards iteration
+        for z in (0..depth).rev() {
+            for y in (0..height).rev() {
+                for x in (0..width).rev() {
+                    for &(neighbour_x, neighbour_y, neighbour_z) in &FORWARD_NEIGHBOURS {
+                        distance_volume.take_nearer_neighbour_target(x, y, z, -neighbour_x, -neighbour_y, -neighbour_z);
+                    }
+                }
+            }
+        }
+
+        // flip the distance sign of every voxel which is inside the shape
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    if binary_volume.is_inside(x, y, z) {
+                        let index = distance_volume.flatten_index(x, y, z);
+                        distance_volume.distances.set(index, - distance_volume.distances.get(index));
+                    }
+                }
+            }
+        }
+
+        distance_volume
+    }
+
+    /// Approximates the signed distance volume of the specified binary volume, like `compute`.
+    /// Returns an error if the volume has no voxels, is too large for the target storage, or does not contain any shapes.
+    pub fn try_compute(binary_volume: &impl BinaryVolume) -> Result<Self> {
+        let (width, height, depth) = (binary_volume.width(), binary_volume.height(), binary_volume.depth());
+
+        if width == 0 || height == 0 || depth == 0 {
+            return Err(Error::EmptyImage);
+        }
+
+        let side = width.max(height).max(depth);
+        if side > T::max_side() {
+            return Err(Error::DimensionTooLarge { side, max_side: T::max_side() });
+        }
+
+        let distance_volume = Self::compute(binary_volume);
+        let voxel_count = width as usize * height as usize * depth as usize;
+
+        // quantized storages saturate distant voxels to infinity, so look for any finite distance
+        if (0..voxel_count).any(|index| distance_volume.distances.get(index).is_finite()) { Ok(distance_volume) }
+        else { Err(Error::NoShapeFound) }
+    }
+
+    /// If the distance through the specified neighbour is smaller than the own distance,
+    /// take the target of that neighbour and update the own distance.
+    #[inline(always)]
+    fn take_nearer_neighbour_target(
+        &mut self, x: u32, y: u32, z: u32,
+        neighbour_x: i64, neighbour_y: i64, neighbour_z: i64
+    ) {
+        let distance_to_neighbour = length(neighbour_x, neighbour_y, neighbour_z);
+        let neighbour_x = x as i64 + neighbour_x;
+        let neighbour_y = y as i64 + neighbour_y;
+        let neighbour_z = z as i64 + neighbour_z;
+
+        if !self.is_valid_index(neighbour_x, neighbour_y, neighbour_z) {
+            return;
+        }
+
+        let neighbour_index = self.flatten_index(neighbour_x as u32, neighbour_y as u32, neighbour_z as u32);
+        if self.distances.get(neighbour_index) + distance_to_neighbour < self.get_distance(x, y, z) {
+            let target = self.target_at(neighbour_index);
+            let distance = distance((x, y, z), target);
+            self.set_target_with_distance(x, y, z, target, distance);
+        }
+    }
+
+    /// Returns the distance of the specified voxel to the nearest edge in the original volume.
+    #[inline(always)]
+    pub fn get_distance(&self, x: u32, y: u32, z: u32) -> f32 {
+        self.distances.get(self.flatten_index(x, y, z))
+    }
+
+    /// Returns the absolute index of the nearest edge to the specified voxel in the original volume.
+    #[inline(always)]
+    pub fn get_distance_target(&self, x: u32, y: u32, z: u32) -> (u32, u32, u32) {
+        self.target_at(self.flatten_index(x, y, z))
+    }
+
+    #[inline(always)]
+    fn target_at(&self, index: usize) -> (u32, u32, u32) {
+        self.distance_targets.get(index)
+    }
+
+    /// Update the distance and target volume at the specified voxel index
+    #[inline(always)]
+    fn set_target_with_distance(&mut self, x: u32, y: u32, z: u32, target: (u32, u32, u32), distance: f32) {
+        let index = self.flatten_index(x, y, z);
+        self.distances.set(index, distance);
+        self.distance_targets.set(index, target);
+    }
+
+    /// Check if x, y and z are valid voxel coordinates inside this volume.
+    #[inline]
+    fn is_valid_index(&self, x: i64, y: i64, z: i64) -> bool {
+        is_valid_index(x, y, z, self.width, self.height, self.depth)
+    }
+
+    /// Convert x, y and z voxel coordinates to the corresponding
+    /// one-dimensional index in a vector of row-major images.
+    #[inline(always)]
+    pub fn flatten_index(&self, x: u32, y: u32, z: u32) -> usize {
+        debug_assert!(
+            self.is_valid_index(x as i64, y as i64, z as i64),
+            "Invalid voxel target index"
+        );
+
+        (z as usize * self.height as usize + y as usize) * self.width as usize + x as usize
+    }
+
+    /// The number of voxels in this volume.
+    #[inline]
+    fn voxel_count(&self) -> usize {
+        self.width as usize * self.height as usize * self.depth as usize
+    }
+
+    /// Scales all distances such that the smallest distance is zero and the largest is one.
+    /// Also computes the former minimum and maximum distance, as well as the new edge-value.
+    /// Returns `None` if the binary volume did not contain any shapes.
+    pub fn normalize_distances(self) -> Option<NormalizedDistanceVolume<D, T>> {
+        NormalizedDistanceVolume::normalize(self)
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one.
+    /// All distances smaller than `min` and larger than `max` will be clamped.
+    /// Edges (formerly zero-distances) will be at the center, put to `0.5`.
+    /// Also collects the former minimum and maximum distance.
+    /// Returns `None` if the binary volume did not contain any shapes.
+    pub fn normalize_clamped_distances(self, min: f32, max: f32) -> Option<NormalizedDistanceVolume<D, T>> {
+        NormalizedDistanceVolume::normalize_clamped(self, min, max)
+    }
+
+    /// Scales all distances such that the smallest distance is zero and the largest is one,
+    /// like `normalize_distances`, but reports why the distances could not be normalized.
+    pub fn try_normalize_distances(self) -> Result<NormalizedDistanceVolume<D, T>> {
+        NormalizedDistanceVolume::try_normalize(self)
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one,
+    /// like `normalize_clamped_distances`, but reports why the distances could not be normalized.
+    pub fn try_normalize_clamped_distances(self, min: f32, max: f32) -> Result<NormalizedDistanceVolume<D, T>> {
+        NormalizedDistanceVolume::try_normalize_clamped(self, min, max)
+    }
+}
+
+/// Returns if the binary volume contains an edge
+/// at the specified voxel compared to any of its six direct neighbours.
+#[inline(always)]
+fn is_at_any_edge(volume: &impl BinaryVolume, x: u32, y: u32, z: u32) -> bool {
+    let is_inside = volume.is_inside(x, y, z);
+
+    [(-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1)].iter()
+        .any(|&(neighbour_x, neighbour_y, neighbour_z)| {
+            let neighbour_x = x as i64 + neighbour_x;
+            let neighbour_y = y as i64 + neighbour_y;
+            let neighbour_z = z as i64 + neighbour_z;
+
+            is_valid_index(neighbour_x, neighbour_y, neighbour_z, volume.width(), volume.height(), volume.depth())
+                && is_inside != volume.is_inside(neighbour_x as u32, neighbour_y as u32, neighbour_z as u32)
+        })
+}
+
+/// The length of a vector with x, y and z coordinates.
+#[inline]
+fn length(x: i64, y: i64, z: i64) -> f32 {
+    ((x * x + y * y + z * z) as f32).sqrt()
+}
+
+/// The distance between two points with x, y and z coordinates.
+#[inline]
+fn distance((x, y, z): (u32, u32, u32), (target_x, target_y, target_z): (u32, u32, u32)) -> f32 {
+    length(x as i64 - target_x as i64, y as i64 - target_y as i64, z as i64 - target_z as i64)
+}
+
+/// Check if x, y and z are valid voxel coordinates
+/// inside a volume with the specified width, height and depth.
+#[inline]
+fn is_valid_index(x: i64, y: i64, z: i64, width: u32, height: u32, depth: u32) -> bool {
+    x >= 0 && y >= 0 && z >= 0 && x < width as i64 && y < height as i64 && z < depth as i64
+}
+
+/// Scale the value so that it fits into the range `[0,1]`.
+#[inline]
+fn normalize(value: f32, min: f32, max: f32) -> f32 {
+    (value - min) / (max - min)
+}
+
+
+impl<D, T> NormalizedDistanceVolume<D, T> where D: DistanceStorage, T: VolumeTargetStorage {
+
+    /// Scales all distances such that the smallest distance is zero and the largest is one.
+    /// Also computes the former minimum and maximum distance, as well as the new edge-value.
+    /// Returns `None` if the binary volume did not contain any shapes.
+    pub fn normalize(distance_volume: SignedDistanceVolume<D, T>) -> Option<Self> {
+        Self::try_normalize(distance_volume).ok()
+    }
+
+    /// Scales all distances such that the smallest distance is zero and the largest is one.
+    /// Also computes the former minimum and maximum distance, as well as the new edge-value.
+    /// Returns an error if the binary volume did not contain any shapes,
+    /// or if all distances are equal.
+    pub fn try_normalize(distance_volume: SignedDistanceVolume<D, T>) -> Result<Self> {
+        let mut distance_volume = distance_volume;
+        let length = distance_volume.voxel_count();
+
+        let (min, max) = (0..length)
+            .map(|index| distance_volume.distances.get(index))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), distance|
+                (min.min(distance), max.max(distance))
+            );
+
+        if min.is_infinite() || max.is_infinite() {
+            return Err(Error::NoShapeFound);
+        }
+
+        if min == max {
+            return Err(Error::ZeroSizeRange);
+        }
+
+        for index in 0..length {
+            let distance = distance_volume.distances.get(index);
+            distance_volume.distances.set(index, normalize(distance, min, max));
+        }
+
+        Ok(NormalizedDistanceVolume {
+            width: distance_volume.width,
+            height: distance_volume.height,
+            depth: distance_volume.depth,
+            distances: distance_volume.distances,
+            zero_distance: normalize(0.0, min, max),
+            former_max_distance: max, former_min_distance: min,
+            distance_targets: distance_volume.distance_targets
+        })
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one.
+    /// All distances smaller than `min` and larger than `max` will be clamped.
+    /// Edges (formerly zero-distances) will be put at exactly the middle between `min` and `max`,
+    /// being `0.5` if `min == -max`.
+    ///
+    /// Also collects the former minimum and maximum distance.
+    /// Returns `None` if the binary volume did not contain any shapes.
+    pub fn normalize_clamped(distance_volume: SignedDistanceVolume<D, T>, min: f32, max: f32) -> Option<Self> {
+        Self::try_normalize_clamped(distance_volume, min, max).ok()
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one,
+    /// like `normalize_clamped`.
+    /// Returns an error if the binary volume did not contain any shapes,
+    /// or if `min` and `max` are equal.
+    pub fn try_normalize_clamped(distance_volume: SignedDistanceVolume<D, T>, min: f32, max: f32) -> Result<Self> {
+        if min == max {
+            return Err(Error::ZeroSizeRange);
+        }
+
+        let mut normalized = NormalizedDistanceVolume {
+            width: distance_volume.width,
+            height: distance_volume.height,
+            depth: distance_volume.depth,
+            distances: distance_volume.distances,
+            former_min_distance: f32::INFINITY,
+            former_max_distance: f32::NEG_INFINITY,
+            zero_distance: normalize(0.0, min, max),
+            distance_targets: distance_volume.distance_targets
+        };
+
+        for index in 0..normalized.voxel_count() {
+            let distance = normalized.distances.get(index);
+            if distance.is_infinite() { return Err(Error::NoShapeFound); }
+
+            normalized.former_max_distance = normalized.former_max_distance.max(distance);
+            normalized.former_min_distance = normalized.former_min_distance.min(distance);
+
+            let clamped = distance.min(max).max(min);
+            normalized.distances.set(index, normalize(clamped, min, max));
+        }
+
+        Ok(normalized)
+    }
+
+    /// The number of voxels in this volume.
+    #[inline]
+    fn voxel_count(&self) -> usize {
+        self.width as usize * self.height as usize * self.depth as usize
+    }
+
+    /// Convert the normalized distance to an `u8` volume with the range fully utilized.
+    pub fn to_u8(&self) -> Vec<u8> {
+        (0..self.voxel_count())
+            .map(|index| (self.distances.get(index).clamp(0.0, 1.0) * u8::MAX as f32) as u8)
+            .collect()
+    }
+
+    /// Convert the normalized distance to an `u16` volume with the range fully utilized.
+    pub fn to_u16(&self) -> Vec<u16> {
+        (0..self.voxel_count())
+            .map(|index| (self.distances.get(index).clamp(0.0, 1.0) * u16::MAX as f32) as u16)
+            .collect()
+    }
+}
+
+impl VolumeTargetStorage for U16VolumeTargetStorage {
+    fn new(length: usize) -> Self {
+        vec![(0, 0, 0); length]
+    }
+
+    fn max_side() -> u32 {
+        u16::MAX as u32 + 1
+    }
+
+    #[inline(always)]
+    fn get(&self, index: usize) -> (u32, u32, u32) {
+        let (x, y, z) = self[index];
+        (x as u32, y as u32, z as u32)
+    }
+
+    #[inline(always)]
+    fn set(&mut self, index: usize, (x, y, z): (u32, u32, u32)) {
+        self[index] = (x as u16, y as u16, z as u16)
+    }
+}
+
+impl VolumeTargetStorage for U32VolumeTargetStorage {
+    fn new(length: usize) -> Self {
+        vec![(0, 0, 0); length]
+    }
+
+    fn max_side() -> u32 {
+        u32::MAX
+    }
+
+    #[inline(always)]
+    fn get(&self, index: usize) -> (u32, u32, u32) {
+        self[index]
+    }
+
+    #[inline(always)]
+    fn set(&mut self, index: usize, target: (u32, u32, u32)) {
+        self[index] = target
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_volume;
+    use crate::distance_field::{F16DistanceStorage, F32DistanceStorage, U8DistanceStorage};
+
+    fn sphere_volume(side: u32, radius: f32) -> Vec<u8> {
+        let center = side as f32 * 0.5;
+        let mut voxels = Vec::with_capacity((side * side * side) as usize);
+
+        for z in 0..side {
+            for y in 0..side {
+                for x in 0..side {
+                    let (dx, dy, dz) = (x as f32 - center, y as f32 - center, z as f32 - center);
+                    let inside = (dx * dx + dy * dy + dz * dz).sqrt() < radius;
+                    voxels.push(if inside { 255 } else { 0 });
+                }
+            }
+        }
+
+        voxels
+    }
+
+    #[test]
+    fn reconstruct_sphere() {
+        let (side, radius) = (40, 12.0);
+        let voxels = sphere_volume(side, radius);
+        let volume = binary_volume::of_byte_slice(&voxels, side, side, side);
+
+        let distance_volume = SignedDistanceVolume::<F32DistanceStorage>::compute(&volume);
+        let center = side as f32 * 0.5;
+
+        for z in 0..side {
+            for y in 0..side {
+                for x in 0..side {
+                    let (dx, dy, dz) = (x as f32 - center, y as f32 - center, z as f32 - center);
+                    let expected = (dx * dx + dy * dy + dz * dz).sqrt() - radius;
+                    let actual = distance_volume.get_distance(x, y, z);
+
+                    assert!(
+                        (expected - actual).abs() < 1.5,
+                        "distance at ({}, {}, {}) should be {} but was {}", x, y, z, expected, actual
+                    );
+
+                    assert_eq!(actual.is_sign_negative(), volume.is_inside(x, y, z));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn distance_targets_point_to_edges() {
+        let side = 16;
+        let voxels = sphere_volume(side, 5.0);
+        let volume = binary_volume::of_byte_slice(&voxels, side, side, side);
+        let distance_volume = SignedDistanceVolume::<F32DistanceStorage>::compute(&volume);
+
+        for z in 0..side {
+            for y in 0..side {
+                for x in 0..side {
+                    let target = distance_volume.get_distance_target(x, y, z);
+                    assert_eq!(distance_volume.get_distance(target.0, target.1, target.2), 0.0);
+
+                    let expected = distance((x, y, z), target);
+                    assert_eq!(distance_volume.get_distance(x, y, z).abs(), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wide_volume() {
+        let width = 70_000;
+
+        // a single inside voxel at the far right end
+        let mut voxels = vec![0_u8; width * 3 * 3];
+        voxels[(3 + 1) * width + width - 2] = 255;
+
+        let volume = binary_volume::of_byte_slice(&voxels, width as u32, 3, 3);
+        let distance_volume: SignedDistanceVolume<F32DistanceStorage, U32VolumeTargetStorage> = SignedDistanceVolume::compute(&volume);
+
+        assert_eq!(distance_volume.get_distance(0, 1, 1), (width - 3) as f32);
+        assert_eq!(distance_volume.get_distance_target(0, 1, 1), (width as u32 - 3, 1, 1));
+        assert_eq!(distance_volume.get_distance(width as u32 - 2, 1, 1), -0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Volume too large for target storage")]
+    fn wide_volume_does_not_fit_u16_targets() {
+        let width = 70_000;
+        let voxels = vec![0_u8; width];
+        let volume = binary_volume::of_byte_slice(&voxels, width as u32, 1, 1);
+        SignedDistanceVolume::<F32DistanceStorage>::compute(&volume);
+    }
+
+    #[test]
+    fn normalize_volume() {
+        let side = 16;
+        let voxels = sphere_volume(side, 5.0);
+        let volume = binary_volume::of_byte_slice(&voxels, side, side, side);
+
+        let normalized = SignedDistanceVolume::<F16DistanceStorage>::compute(&volume)
+            .normalize_distances().unwrap();
+
+        assert!(normalized.former_min_distance < 0.0);
+        assert!(normalized.former_max_distance > 0.0);
+        assert!(normalized.zero_distance > 0.0 && normalized.zero_distance < 1.0);
+        assert_eq!(normalized.to_u8().len(), (side * side * side) as usize);
+        assert!(normalized.to_u8().contains(&0) && normalized.to_u8().contains(&255));
+
+        let clamped = SignedDistanceVolume::<F32DistanceStorage>::compute(&volume)
+            .normalize_clamped_distances(-2.0, 2.0).unwrap();
+
+        assert_eq!(clamped.zero_distance, 0.5);
+        assert!(clamped.to_u16().contains(&0) && clamped.to_u16().contains(&u16::MAX));
+    }
+
+    #[test]
+    fn empty_volume_is_infinite() {
+        let voxels = vec![0; 4 * 4 * 4];
+        let volume = binary_volume::of_byte_slice(&voxels, 4, 4, 4);
+        let distance_volume = SignedDistanceVolume::<F32DistanceStorage>::compute(&volume);
+
+        assert!(distance_volume.get_distance(2, 2, 2).is_infinite());
+        assert!(distance_volume.normalize_distances().is_none());
+    }
+
+    #[test]
+    fn empty_volume_reports_error() {
+        let voxels = vec![0; 4 * 4 * 4];
+
+        assert_eq!(
+            binary_volume::try_of_byte_slice(&voxels, 4, 4, 3).err(),
+            Some(Error::DimensionMismatch { expected: 48, actual: 64 })
+        );
+
+        let volume = binary_volume::try_of_byte_slice(&voxels, 4, 4, 4).unwrap();
+        assert_eq!(SignedDistanceVolume::<F32DistanceStorage>::try_compute(&volume).err(), Some(Error::NoShapeFound));
+
+        // quantized storages saturate the voxels far away from the shape
+        let corner_voxels = (0..32 * 32 * 32).map(|index| if index == 32 * 32 * 32 - 1 { 255 } else { 0 }).collect::<Vec<u8>>();
+        let corner_volume = binary_volume::of_byte_slice(&corner_voxels, 32, 32, 32);
+        assert!(SignedDistanceVolume::<U8DistanceStorage<4>>::try_compute(&corner_volume).is_ok());
+
+        let wide_voxels = vec![0; 70_000];
+        let wide_volume = binary_volume::try_of_byte_slice(&wide_voxels, 70_000, 1, 1).unwrap();
+        assert_eq!(
+            SignedDistanceVolume::<F32DistanceStorage>::try_compute(&wide_volume).err(),
+            Some(Error::DimensionTooLarge { side: 70_000, max_side: 65536 })
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// The reasons why constructing, computing or normalizing a distance field can fail.
+/// Returned by the `try_` variants of the functions in this crate.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// The length of a buffer does not match the specified dimensions.
+    DimensionMismatch {
+        /// The buffer length required by the specified dimensions.
+        expected: usize,
+
+        /// The actual length of the buffer.
+        actual: usize,
+    },
+
+    /// The image or volume has a width, height or depth of zero.
+    EmptyImage,
+
+    /// The image or volume has a side which is longer than the target storage supports.
+    DimensionTooLarge {
+        /// The longest side of the image or volume.
+        side: u32,
+
+        /// The longest side which the target storage supports.
+        max_side: u32,
+    },
+
+    /// The image does not contain any edges,
+    /// so all distances would be `INFINITY`.
+    NoShapeFound,
+
+    /// The range to normalize distances into has no size,
+    /// because its minimum and maximum are equal.
+    ZeroSizeRange,
+
+    /// The font data could not be parsed as a TrueType or OpenType font.
+    InvalidFont,
+}
+
+/// The result of all fallible functions in this crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DimensionMismatch { expected, actual } => write!(
+                formatter, "buffer dimension mismatch: expected {} elements, but got {}", expected, actual
+            ),
+
+            Error::EmptyImage => write!(formatter, "the image does not contain any pixels"),
+
+            Error::DimensionTooLarge { side, max_side } => write!(
+                formatter, "the image side of {} pixels exceeds the maximum of {} supported by the target storage", side, max_side
+            ),
+
+            Error::NoShapeFound => write!(formatter, "the image does not contain any shapes"),
+            Error::ZeroSizeRange => write!(formatter, "the distance range has a size of zero"),
+            Error::InvalidFont => write!(formatter, "the font data is not a valid TrueType or OpenType font"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
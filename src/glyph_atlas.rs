@@ -0,0 +1,345 @@
+//! Generates a texture atlas containing the distance fields of the glyphs of a font.
+//! Requires the feature `font`. Supports TrueType and OpenType fonts.
+
+use crate::distance_field::{ SignedDistanceField, F32DistanceStorage, DiscardedTargetStorage };
+use crate::shape::{ Shape, PathBuilder, FillRule, Point };
+use crate::error::{ Error, Result };
+use crate::atlas::{ AtlasPacker, PackingAlgorithm };
+use std::fmt::Write;
+
+/// Configures how the glyphs of a font are converted to distance fields.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GlyphAtlasSettings {
+    /// The size of one em in pixels, which is roughly the height of a line.
+    pub font_size: f32,
+
+    /// The number of pixels added around each glyph,
+    /// which should be at least the distance range.
+    pub padding: u32,
+
+    /// The distances between `-distance_range` and `distance_range` pixels
+    /// are mapped to the range of the atlas bytes, with edges at 128.
+    pub distance_range: f32,
+
+    /// The width of the atlas in pixels, unless a single glyph is wider.
+    /// The height grows with the number of glyphs.
+    pub atlas_width: u32,
+}
+
+/// The placement and the metrics of a single glyph in the atlas.
+/// All lengths are measured in pixels, with the y axis pointing downwards.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GlyphMetrics {
+    pub character: char,
+
+    /// The horizontal distance from the origin of this glyph to the origin of the next glyph.
+    pub advance: f32,
+
+    /// The offset from the origin of this glyph, on the baseline,
+    /// to the top left corner of its rectangle in the atlas, including the padding.
+    pub bearing: (i32, i32),
+
+    /// The top left corner of the rectangle of this glyph in the atlas.
+    pub position: (u32, u32),
+
+    /// The width and height of the rectangle of this glyph in the atlas, including the padding.
+    /// Glyphs without outline, like spaces, have a size of zero.
+    pub size: (u32, u32),
+
+    /// The rectangle of this glyph in texture coordinates, in the range `[0, 1]`,
+    /// as `(left, top, right, bottom)`.
+    pub uv: (f32, f32, f32, f32),
+
+    /// The number of pixels around the outline of the glyph, on each side of the rectangle.
+    pub padding: u32,
+}
+
+/// A single channel texture containing the distance fields of many glyphs,
+/// along with the metrics required to lay out text.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GlyphAtlas {
+    pub width: u32,
+    pub height: u32,
+
+    /// A row-major image vector with one normalized distance per pixel,
+    /// where inside distances are darker than the edge value of 128.
+    pub distances: Vec<u8>,
+
+    /// The metrics of every requested character which exists in the font.
+    pub glyphs: Vec<GlyphMetrics>,
+
+    /// The settings used to generate this atlas.
+    pub settings: GlyphAtlasSettings,
+
+    /// The distance from the baseline to the top of the highest glyphs in pixels.
+    pub ascender: f32,
+
+    /// The distance from the baseline to the bottom of the lowest glyphs in pixels, usually negative.
+    pub descender: f32,
+
+    /// The additional space between two lines in pixels.
+    pub line_gap: f32,
+}
+
+
+impl Default for GlyphAtlasSettings {
+    fn default() -> Self {
+        GlyphAtlasSettings { font_size: 32.0, padding: 4, distance_range: 4.0, atlas_width: 512 }
+    }
+}
+
+impl GlyphAtlas {
+    /// Computes the distance fields of the characters from the vector outlines of the font,
+    /// and packs them into a single atlas. Characters which the font does not contain are skipped.
+    /// The font data is the content of a TrueType or OpenType file, for example read with `std::fs::read`.
+    /// Returns an error if the font cannot be parsed or if the distance range is not positive.
+    pub fn generate(font_data: &[u8], characters: impl IntoIterator<Item = char>, settings: GlyphAtlasSettings) -> Result<Self> {
+        if settings.distance_range <= 0.0 { return Err(Error::ZeroSizeRange); }
+
+        let face = ttf_parser::Face::parse(font_data, 0).map_err(|_| Error::InvalidFont)?;
+        let scale = settings.font_size / face.units_per_em() as f32;
+
+        let mut glyphs = Vec::new();
+        let mut fields = Vec::new();
+
+        for character in characters {
+            let glyph = match face.glyph_index(character) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+
+            if glyphs.iter().any(|metrics: &GlyphMetrics| metrics.character == character) {
+                continue;
+            }
+
+            let advance = face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+
+            let (bearing, size, distances) = match face.glyph_bounding_box(glyph) {
+                Some(bounds) => {
+                    let padding = settings.padding as i32;
+                    let left = (bounds.x_min as f32 * scale).floor() as i32 - padding;
+                    let top = (-bounds.y_max as f32 * scale).floor() as i32 - padding;
+                    let right = (bounds.x_max as f32 * scale).ceil() as i32 + padding;
+                    let bottom = (-bounds.y_min as f32 * scale).ceil() as i32 + padding;
+                    let size = ((right - left) as u32, (bottom - top) as u32);
+
+                    let mut outline = GlyphOutline { builder: PathBuilder::new(), scale, offset: (left as f32, top as f32) };
+                    face.outline_glyph(glyph, &mut outline);
+
+                    let distances = glyph_distances(&outline.builder.build(), size, settings.distance_range);
+                    ((left, top), size, distances)
+                },
+
+                None => ((0, 0), (0, 0), Vec::new()),
+            };
+
+            glyphs.push(GlyphMetrics {
+                character, advance, bearing, size,
+                position: (0, 0), uv: (0.0, 0.0, 0.0, 0.0),
+                padding: settings.padding,
+            });
+
+            fields.push(distances);
+        }
+
+        // the glyph rectangles already contain the padding
+        let sizes: Vec<(u32, u32)> = glyphs.iter().map(|glyph| glyph.size).collect();
+        let (placements, width, height) = AtlasPacker::new(settings.atlas_width, 0, PackingAlgorithm::Shelf).pack(&sizes);
+        let mut atlas = vec![ u8::MAX; width as usize * height as usize ];
+
+        for ((glyph, distances), placement) in glyphs.iter_mut().zip(&fields).zip(placements) {
+            let (glyph_width, glyph_height) = glyph.size;
+            let position = (placement.x, placement.y);
+
+            for y in 0 .. glyph_height as usize {
+                let row = &distances[y * glyph_width as usize .. (y + 1) * glyph_width as usize];
+                let start = (position.1 as usize + y) * width as usize + position.0 as usize;
+                atlas[start .. start + glyph_width as usize].copy_from_slice(row);
+            }
+
+            glyph.position = position;
+            glyph.uv = (
+                position.0 as f32 / width as f32,
+                position.1 as f32 / height.max(1) as f32,
+                (position.0 + glyph_width) as f32 / width as f32,
+                (position.1 + glyph_height) as f32 / height.max(1) as f32,
+            );
+        }
+
+        Ok(GlyphAtlas {
+            width, height, distances: atlas, glyphs, settings,
+            ascender: face.ascender() as f32 * scale,
+            descender: face.descender() as f32 * scale,
+            line_gap: face.line_gap() as f32 * scale,
+        })
+    }
+
+    /// Returns the metrics of the character, if the atlas contains it.
+    pub fn glyph(&self, character: char) -> Option<&GlyphMetrics> {
+        self.glyphs.iter().find(|glyph| glyph.character == character)
+    }
+
+    /// Describes the atlas size, the font metrics and the metrics of all glyphs as JSON.
+    pub fn metrics_to_json(&self) -> String {
+        let mut json = String::new();
+
+        write!(
+            json, "{{\n  \"width\": {}, \"height\": {}, \"font_size\": {}, \"distance_range\": {}, \
+                \"ascender\": {}, \"descender\": {}, \"line_gap\": {},\n  \"glyphs\": [",
+            self.width, self.height, self.settings.font_size, self.settings.distance_range,
+            self.ascender, self.descender, self.line_gap
+        ).unwrap();
+
+        for (index, glyph) in self.glyphs.iter().enumerate() {
+            if index > 0 { json.push(','); }
+
+            write!(
+                json, "\n    {{ \"character\": {}, \"code_point\": {}, \"advance\": {}, \"bearing\": [{}, {}], \
+                    \"position\": [{}, {}], \"size\": [{}, {}], \"uv\": [{}, {}, {}, {}], \"padding\": {} }}",
+                json_string(glyph.character), glyph.character as u32, glyph.advance,
+                glyph.bearing.0, glyph.bearing.1, glyph.position.0, glyph.position.1,
+                glyph.size.0, glyph.size.1, glyph.uv.0, glyph.uv.1, glyph.uv.2, glyph.uv.3, glyph.padding
+            ).unwrap();
+        }
+
+        json.push_str("\n  ]\n}\n");
+        json
+    }
+
+    /// Convert the atlas to a grey-scale piston image.
+    #[cfg(feature = "piston_image")]
+    pub fn to_gray_u8_image(&self) -> image::GrayImage {
+        image::GrayImage::from_raw(self.width, self.height, self.distances.clone())
+            .expect("incorrect vector length")
+    }
+}
+
+/// Converts the outline of a glyph to a shape, flipping the y axis,
+/// scaling to pixels and moving the top left corner of the glyph rectangle to the origin.
+struct GlyphOutline {
+    builder: PathBuilder,
+    scale: f32,
+    offset: (f32, f32),
+}
+
+impl GlyphOutline {
+    fn point(&self, x: f32, y: f32) -> Point {
+        (x * self.scale - self.offset.0, -y * self.scale - self.offset.1)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.builder.move_to(point);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.builder.line_to(point);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (control, point) = (self.point(x1, y1), self.point(x, y));
+        self.builder.quadratic_to(control, point);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (first_control, second_control, point) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.builder.cubic_to(first_control, second_control, point);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+/// Computes the exact distance field of the shape and normalizes it to bytes.
+/// The bytes are rounded, so that distances of zero are exactly 128.
+fn glyph_distances(shape: &Shape, (width, height): (u32, u32), range: f32) -> Vec<u8> {
+    let field = SignedDistanceField::<F32DistanceStorage, DiscardedTargetStorage>
+        ::compute_shape(shape, width, height, FillRule::NonZero);
+
+    match field.normalize_clamped_distances(-range, range) {
+        Some(normalized) => normalized.distances.iter()
+            .map(|&distance| (distance.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8)
+            .collect(),
+
+        None => vec![ u8::MAX; width as usize * height as usize ],
+    }
+}
+
+/// Encodes the character as a quoted JSON string.
+fn json_string(character: char) -> String {
+    match character {
+        '"' => "\"\\\"\"".to_string(),
+        '\\' => "\"\\\\\"".to_string(),
+        control if control.is_control() => format!("\"\\u{:04x}\"", control as u32),
+        other => format!("\"{}\"", other),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use super::{ json_string, glyph_distances };
+
+    const FONT_PATH: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+    #[test]
+    pub fn characters_are_escaped(){
+        assert_eq!(json_string('a'), "\"a\"");
+        assert_eq!(json_string('"'), "\"\\\"\"");
+        assert_eq!(json_string('\n'), "\"\\u000a\"");
+    }
+
+    #[test]
+    pub fn edges_are_exactly_128(){
+        // the left outline passes through the centers of the pixels in the third column
+        let shape = Shape::new(vec![ Contour::polygon(&[ (2.5, -1.0), (9.0, -1.0), (9.0, 9.0), (2.5, 9.0) ]) ]);
+        let distances = glyph_distances(&shape, (8, 8), 3.0);
+
+        for y in 0..8 {
+            let row = &distances[y * 8 .. (y + 1) * 8];
+            assert_eq!(row[2], 128);
+            assert!(row[1] > 128);
+            assert!(row[3] < 128);
+        }
+    }
+
+    #[test]
+    pub fn invalid_font_reports_error(){
+        let result = GlyphAtlas::generate(&[1, 2, 3], "abc".chars(), GlyphAtlasSettings::default());
+        assert_eq!(result.unwrap_err(), Error::InvalidFont);
+    }
+
+    #[test]
+    #[ignore = "requires the DejaVu Sans font at /usr/share/fonts/truetype/dejavu/DejaVuSans.ttf"]
+    pub fn generate_atlas_from_font_file(){
+        let font = std::fs::read(FONT_PATH).expect("the font file is missing");
+
+        let settings = GlyphAtlasSettings { font_size: 24.0, padding: 3, distance_range: 3.0, atlas_width: 128 };
+        let atlas = GlyphAtlas::generate(&font, "Hello, World!".chars(), settings).unwrap();
+
+        assert_eq!(atlas.glyphs.len(), "Helo, Wrd!".len());
+        assert_eq!(atlas.distances.len(), atlas.width as usize * atlas.height as usize);
+        assert!(atlas.ascender > 0.0 && atlas.descender < 0.0);
+
+        let space = atlas.glyph(' ').unwrap();
+        assert_eq!(space.size, (0, 0));
+        assert!(space.advance > 0.0);
+
+        // the center of the stem of the `l` is inside, the padding is outside
+        let l = atlas.glyph('l').unwrap();
+        let (x, y) = (l.position.0 + l.size.0 / 2, l.position.1 + l.size.1 / 2);
+        assert!(atlas.distances[(y * atlas.width + x) as usize] < 128);
+        assert!(atlas.distances.contains(&128));
+        assert_eq!(atlas.distances[(l.position.1 * atlas.width + l.position.0) as usize], 255);
+        assert!(l.bearing.1 < 0 && l.uv.2 > l.uv.0);
+
+        let json = atlas.metrics_to_json();
+        assert!(json.contains("\"character\": \"W\""));
+        assert_eq!(json.matches("\"advance\"").count(), atlas.glyphs.len());
+    }
+}
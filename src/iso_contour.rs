@@ -0,0 +1,219 @@
+use std::collections::{ BTreeMap, BTreeSet };
+use crate::shape::Point;
+
+/// A line through a sequence of points, as extracted by `iso_contours`.
+/// Points are measured in pixels, where the distance of pixel `(x, y)` is located at `(x + 0.5, y + 0.5)`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Polyline {
+    /// The points of this line, with the inside of the shape on the right side
+    /// when following the points in image coordinates, where y points downwards.
+    /// The first point is not repeated at the end of closed lines.
+    pub points: Vec<Point>,
+
+    /// Whether the last point is connected to the first point.
+    /// Lines are only open where they leave the image.
+    pub is_closed: bool,
+}
+
+/// Extracts the lines where the distances are equal to the iso value, using marching squares.
+/// The positions of the points are linearly interpolated between the pixels.
+/// An iso value of zero extracts the outline of the shape,
+/// while other iso values extract the outline of the shape grown by that distance.
+/// Ambiguous pixel configurations are resolved by the average distance of the four pixels.
+pub fn iso_contours(width: u32, height: u32, distance: impl Fn(u32, u32) -> f32, iso_value: f32) -> Vec<Polyline> {
+    if width < 2 || height < 2 {
+        return Vec::new();
+    }
+
+    let edge_index = |x: u32, y: u32, is_vertical: bool| {
+        (y as usize * width as usize + x as usize) * 2 + is_vertical as usize
+    };
+
+    // maps the edge where a line enters a cell to the edge where it leaves the cell
+    let mut next_edges = BTreeMap::new();
+
+    for y in 0 .. height - 1 {
+        for x in 0 .. width - 1 {
+            // the corners and edges of the cell, in clockwise order starting at the top left
+            let corners = [ distance(x, y), distance(x + 1, y), distance(x + 1, y + 1), distance(x, y + 1) ];
+            let edges = [
+                edge_index(x, y, false), edge_index(x + 1, y, true),
+                edge_index(x, y + 1, false), edge_index(x, y, true),
+            ];
+
+            // all edges whose corners are on different sides, and whether the edge leaves the inside
+            let mut crossings = [(0, false); 4];
+            let mut crossing_count = 0;
+
+            for corner in 0..4 {
+                let is_inside = corners[corner] < iso_value;
+                if is_inside != (corners[(corner + 1) % 4] < iso_value) {
+                    crossings[crossing_count] = (edges[corner], is_inside);
+                    crossing_count += 1;
+                }
+            }
+
+            match crossing_count {
+                0 => {},
+
+                2 => {
+                    let (leaving, entering) = if crossings[0].1 { (crossings[0].0, crossings[1].0) }
+                        else { (crossings[1].0, crossings[0].0) };
+
+                    next_edges.insert(leaving, entering);
+                },
+
+                _ => {
+                    // if the center is inside, the two inside corners are connected,
+                    // and each line goes around one of the outside corners, and vice versa
+                    let center = corners.iter().sum::<f32>() / 4.0;
+                    let offset = if center < iso_value { 1 } else { 3 };
+
+                    for crossing in 0..4 {
+                        let (edge, is_leaving) = crossings[crossing];
+                        if is_leaving {
+                            next_edges.insert(edge, crossings[(crossing + offset) % 4].0);
+                        }
+                    }
+                },
+            }
+        }
+    }
+
+    // the position of the iso value on an edge between two pixels
+    let point = |edge: usize| -> Point {
+        let pixel = edge / 2;
+        let (x, y) = ((pixel % width as usize) as u32, (pixel / width as usize) as u32);
+        let (next_x, next_y) = if edge % 2 == 1 { (x, y + 1) } else { (x + 1, y) };
+
+        let (start, end) = (distance(x, y), distance(next_x, next_y));
+        let t = (iso_value - start) / (end - start);
+        let t = if t.is_finite() && start.is_finite() && end.is_finite() { t.clamp(0.0, 1.0) } else { 0.5 };
+
+        (
+            x as f32 + 0.5 + t * (next_x - x) as f32,
+            y as f32 + 0.5 + t * (next_y - y) as f32,
+        )
+    };
+
+    let mut polylines = Vec::new();
+
+    // open lines start at an edge which no line enters, at the border of the image
+    let entered: BTreeSet<usize> = next_edges.values().cloned().collect();
+    let starts: Vec<usize> = next_edges.keys().cloned().filter(|edge| !entered.contains(edge)).collect();
+
+    for start in starts {
+        let mut points = vec![ point(start) ];
+        let mut edge = start;
+
+        while let Some(next) = next_edges.remove(&edge) {
+            points.push(point(next));
+            edge = next;
+        }
+
+        polylines.push(Polyline { points, is_closed: false });
+    }
+
+    // all remaining lines are closed
+    while let Some((&start, _)) = next_edges.iter().next() {
+        let mut points = Vec::new();
+        let mut edge = start;
+
+        while let Some(next) = next_edges.remove(&edge) {
+            points.push(point(edge));
+            edge = next;
+        }
+
+        polylines.push(Polyline { points, is_closed: true });
+    }
+
+    polylines
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::iso_contour::*;
+
+    fn circle_field(width: u32, height: u32, center: Point, radius: f32) -> SignedDistanceField<F32DistanceStorage> {
+        let shape = PathBuilder::new()
+            .move_to((center.0 + radius, center.1))
+            .cubic_to((center.0 + radius, center.1 + 0.5523 * radius), (center.0 + 0.5523 * radius, center.1 + radius), (center.0, center.1 + radius))
+            .cubic_to((center.0 - 0.5523 * radius, center.1 + radius), (center.0 - radius, center.1 + 0.5523 * radius), (center.0 - radius, center.1))
+            .cubic_to((center.0 - radius, center.1 - 0.5523 * radius), (center.0 - 0.5523 * radius, center.1 - radius), (center.0, center.1 - radius))
+            .cubic_to((center.0 + 0.5523 * radius, center.1 - radius), (center.0 + radius, center.1 - 0.5523 * radius), (center.0 + radius, center.1))
+            .close()
+            .build();
+
+        SignedDistanceField::compute_shape(&shape, width, height, FillRule::NonZero)
+    }
+
+    #[test]
+    pub fn circle_outline_is_closed(){
+        let field = circle_field(40, 40, (20.0, 20.0), 10.0);
+
+        for &iso_value in &[ 0.0, 3.0, -4.0 ] {
+            let contours = field.iso_contours(iso_value);
+            assert_eq!(contours.len(), 1);
+            assert!(contours[0].is_closed);
+            assert!(contours[0].points.len() > 20);
+
+            for &(x, y) in &contours[0].points {
+                let radius = ((x - 20.0).powi(2) + (y - 20.0).powi(2)).sqrt();
+                assert!((radius - (10.0 + iso_value)).abs() < 0.1, "point {}, {} has radius {}", x, y, radius);
+            }
+        }
+    }
+
+    #[test]
+    pub fn inside_is_right_of_the_outline(){
+        let field = circle_field(40, 40, (20.0, 20.0), 10.0);
+        let points = &field.iso_contours(0.0)[0].points;
+
+        // the signed area is positive for clockwise polygons in image coordinates
+        let area: f32 = (0..points.len())
+            .map(|index| {
+                let (x, y) = points[index];
+                let (next_x, next_y) = points[(index + 1) % points.len()];
+                x * next_y - next_x * y
+            })
+            .sum();
+
+        assert!(area > 0.0);
+    }
+
+    #[test]
+    pub fn outline_leaving_the_image_is_open(){
+        let field = circle_field(30, 30, (0.0, 15.0), 10.0);
+        let contours = field.iso_contours(0.0);
+
+        assert_eq!(contours.len(), 1);
+        assert!(!contours[0].is_closed);
+
+        let first = contours[0].points[0];
+        let last = *contours[0].points.last().unwrap();
+        assert_eq!(first.0, 0.5);
+        assert_eq!(last.0, 0.5);
+    }
+
+    #[test]
+    pub fn ambiguous_cells_use_the_center(){
+        let checker = |inside: f32, outside: f32| move |x: u32, y: u32| if (x + y) % 2 == 0 { inside } else { outside };
+
+        // the inside pixels are connected through the center
+        let connected = iso_contours(2, 2, checker(-3.0, 1.0), 0.0);
+        assert_eq!(connected.len(), 2);
+
+        // the inside pixels are separated by the center
+        let separated = iso_contours(2, 2, checker(-1.0, 3.0), 0.0);
+        assert_eq!(separated.len(), 2);
+        assert_ne!(connected, separated);
+    }
+
+    #[test]
+    pub fn empty_field_has_no_contours(){
+        let field = SignedDistanceField::<F32DistanceStorage>::compute_shape(&Shape::default(), 8, 8, FillRule::NonZero);
+        assert!(field.iso_contours(0.0).is_empty());
+    }
+}
@@ -2,12 +2,32 @@
 //! distance field, given a binary image,
 //! as described in "The dead reckoning signed distance transform"
 //! by George J. Grevara (2004).
+//! Alternatively, the exact distance field can be computed
+//! as described in "Distance Transforms of Sampled Functions"
+//! by Pedro F. Felzenszwalb and Daniel P. Huttenlocher (2012).
 
+pub mod error;
 pub mod binary_image;
 pub mod distance_field;
+pub mod shape;
+pub mod multi_channel;
+pub mod iso_contour;
+pub mod morphology;
+pub mod sampler;
+pub mod normal_field;
+pub mod render;
+pub mod atlas;
+pub mod territory;
+pub mod binary_volume;
+pub mod distance_volume;
+
+#[cfg(feature = "font")]
+pub mod glyph_atlas;
 
 pub mod prelude {
-    pub use crate::binary_image::{ self, BinaryImage };
+    pub use crate::error::Error;
+
+    pub use crate::binary_image::{ self, BinaryImage, CoverageImage };
 
     #[cfg(feature = "piston_image")]
     pub use crate::binary_image::piston_image
@@ -18,12 +38,54 @@ pub mod prelude {
         NormalizedDistanceField,
         F16DistanceStorage,
         F32DistanceStorage,
+        U8DistanceStorage,
+        U16DistanceStorage,
+        I16DistanceStorage,
         DistanceStorage,
+        U16TargetStorage,
+        U32TargetStorage,
+        DiscardedTargetStorage,
+        TargetStorage,
+        Wrap,
+        Rectangle,
+        ScratchContext,
+        PixelSet,
+    };
+
+    #[cfg(feature = "parallel")]
+    pub use crate::distance_field::{ ParallelDistanceStorage, ParallelTargetStorage };
+
+    pub use crate::shape::{ Shape, Contour, Segment, Point, FillRule, PathBuilder };
+
+    pub use crate::iso_contour::{ self, Polyline };
+    pub use crate::morphology;
+    pub use crate::sampler::{ Sampler, Interpolation, Border, DistanceGrid };
+    pub use crate::normal_field::{ NormalField, GradientMethod };
+    pub use crate::render::{ self, Renderer, Effect, PixelDistanceGrid };
+    pub use crate::atlas::{ AtlasPacker, PackingAlgorithm, PackedAtlas };
+    pub use crate::territory::{ self, LabelImage, Territories };
+
+    #[cfg(feature = "font")]
+    pub use crate::glyph_atlas::{ GlyphAtlas, GlyphAtlasSettings, GlyphMetrics };
+
+    pub use crate::multi_channel::{
+        MultiChannelDistanceField,
+        NormalizedMultiChannelDistanceField,
+    };
+
+    pub use crate::binary_volume::{ self, BinaryVolume };
+    pub use crate::distance_volume::{
+        SignedDistanceVolume,
+        NormalizedDistanceVolume,
+        U16VolumeTargetStorage,
+        U32VolumeTargetStorage,
+        VolumeTargetStorage,
     };
 
     pub use crate::{
         compute_f16_distance_field,
-        compute_f32_distance_field
+        compute_f32_distance_field,
+        compute_exact_distance_field,
     };
 }
 
@@ -37,6 +99,11 @@ pub fn compute_distance_field<D: DistanceStorage>(image: &impl BinaryImage) -> S
     SignedDistanceField::compute(image)
 }
 
+/// Compute the exact signed distance field of the specified binary image with the specified distance storage.
+pub fn compute_exact_distance_field<D: DistanceStorage>(image: &impl BinaryImage) -> SignedDistanceField<D> {
+    SignedDistanceField::compute_exact(image)
+}
+
 /// Compute the signed distance field of the specified binary image with an `f16` distance storage.
 pub fn compute_f16_distance_field(image: &impl BinaryImage) -> SignedDistanceField<F16DistanceStorage> {
     compute_distance_field(image)
@@ -122,15 +189,15 @@ mod tests {
         }
 
         let binary_image = binary_image::of_byte_slice(
-            &binary_image_buffer, width as u16, height as u16
+            &binary_image_buffer, width as u32, height as u32
         );
 
         let distance_field_16 = compute_f32_distance_field(&binary_image);
         let distance_field_32 = compute_f16_distance_field(&binary_image);
 
         let mut wrong_pixels = 0;
-        for y in 0..height as u16 {
-            for x in 0..width as u16 {
+        for y in 0..height as u32 {
+            for x in 0..width as u32 {
                 let ground_truth = binary_image.is_inside(x, y);
                 let distance_16 = distance_field_16.get_distance(x, y);
                 let distance_32 = distance_field_32.get_distance(x, y);
@@ -178,6 +245,20 @@ mod tests {
         }
     }
 
+    fn box_distance(center_x: usize, center_y: usize, width: usize, height: usize)
+        -> impl Fn(usize, usize) -> f32
+    {
+        move |x,y|{
+            let x = x as f32 - center_x as f32;
+            let y = y as f32 - center_y as f32;
+            let x = x.abs() - width as f32;
+            let y = y.abs() - height as f32;
+            let outside = (x.max(0.0).powi(2) + y.max(0.0).powi(2)).sqrt();
+            let inside = x.max(y).min(0.0);
+            outside + inside
+        }
+    }
+
     #[test]
     pub fn reconstruct_circle_distance_field(){
         reconstruct_distance_field(2048, 2048, 2.0, circle_distance(128, 128, 128));
@@ -203,9 +284,886 @@ mod tests {
         reconstruct_distance_field(2048, 2048, 2.0, rectangle_distance(179, 1023, 4, 7));
     }
 
+    #[test]
+    pub fn reconstruct_exact_circle_distance_field(){
+        reconstruct_exact_distance_field(2048, 2048, 1.0, circle_distance(128, 128, 128));
+    }
+
+    #[test]
+    pub fn reconstruct_exact_large_rectangle_distance_field(){
+        reconstruct_exact_distance_field(2048, 2048, 1.0, box_distance(1024, 1023, 613, 673));
+    }
+
+    #[test]
+    pub fn exact_distance_field_is_exact(){
+        let width = 97;
+        let height = 83;
+        let is_inside = is_inside_circle(40, 30, 20);
+
+        let binary_image_buffer: Vec<u8> = (0..width * height)
+            .map(|index| if is_inside(index % width, index / width) { 255 } else { 0 })
+            .collect();
+
+        let binary_image = binary_image::of_byte_slice(
+            &binary_image_buffer, width as u32, height as u32
+        );
+
+        let is_edge = |x: usize, y: usize| {
+            let neighbours = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+            neighbours.iter().any(|&(neighbour_x, neighbour_y)|
+                neighbour_x < width && neighbour_y < height
+                    && is_inside(x, y) != is_inside(neighbour_x, neighbour_y)
+            )
+        };
+
+        let edges: Vec<(usize, usize)> = (0..width * height)
+            .map(|index| (index % width, index / width))
+            .filter(|&(x, y)| is_edge(x, y))
+            .collect();
+
+        let exact = SignedDistanceField::<F32DistanceStorage>::compute_exact(&binary_image);
+
+        for y in 0..height as u32 {
+            for x in 0..width as u32 {
+                let (target_x, target_y) = exact.get_distance_target(x, y);
+                let target_distance = exact.get_distance(target_x, target_y);
+                assert_eq!(target_distance, 0.0, "target is not an edge");
+
+                let distance = exact.get_distance(x, y);
+                let target_vector = (x as f32 - target_x as f32, y as f32 - target_y as f32);
+                let target_length = (target_vector.0.powi(2) + target_vector.1.powi(2)).sqrt();
+                assert_eq!(distance.abs(), target_length, "distance does not match target");
+
+                assert_eq!(distance.is_sign_negative(), binary_image.is_inside(x, y), "wrong distance sign");
+
+                let nearest_edge = edges.iter()
+                    .map(|&(edge_x, edge_y)| ((x as f32 - edge_x as f32).powi(2) + (y as f32 - edge_y as f32).powi(2)).sqrt())
+                    .fold(f32::INFINITY, f32::min);
+
+                assert_eq!(distance.abs(), nearest_edge, "distance is not exact");
+            }
+        }
+    }
+
+    fn circle_image(width: usize, height: usize, radius: usize) -> Vec<u8> {
+        (0..width * height)
+            .map(|index| if is_inside_circle(width / 2, height / 2, radius)(index % width, index / width) { 255 } else { 0 })
+            .collect()
+    }
+
+    #[test]
+    pub fn quantized_distance_storages(){
+        let buffer = circle_image(80, 60, 16);
+        let binary_image = binary_image::of_byte_slice(&buffer, 80, 60);
+        let reference = SignedDistanceField::<F32DistanceStorage>::compute(&binary_image);
+
+        let u8_field = SignedDistanceField::<U8DistanceStorage<8>>::compute(&binary_image);
+        let u16_field = SignedDistanceField::<U16DistanceStorage<8>>::compute(&binary_image);
+        let i16_field = SignedDistanceField::<I16DistanceStorage>::compute(&binary_image);
+
+        for y in 0..60 {
+            for x in 0..80 {
+                let expected = reference.get_distance(x, y);
+                let index = reference.flatten_index(x, y);
+
+                if expected.abs() < 7.0 {
+                    // quantized comparisons may choose a slightly different nearest edge
+                    assert!((u8_field.get_distance(x, y) - expected).abs() < 0.5, "u8 at {}, {}", x, y);
+                    assert!((u16_field.get_distance(x, y) - expected).abs() < 0.5, "u16 at {}, {}", x, y);
+                }
+
+                else if expected.abs() > 9.0 {
+                    let saturated = if expected < 0.0 { 0 } else { u8::MAX };
+                    assert_eq!(u8_field.distances.values[index], saturated, "u8 at {}, {}", x, y);
+                }
+
+                assert!((i16_field.get_distance(x, y) - expected).abs() < 0.5, "i16 at {}, {}", x, y);
+
+                if expected == 0.0 {
+                    assert_eq!(u8_field.distances.values[index], 128);
+                    assert_eq!(u16_field.distances.values[index], 32768);
+                    assert_eq!(i16_field.distances.values[index], 0);
+                }
+            }
+        }
+
+        let exact = SignedDistanceField::<F32DistanceStorage>::compute_exact(&binary_image);
+        let exact_u8 = SignedDistanceField::<U8DistanceStorage<8>>::compute_exact(&binary_image);
+
+        for y in 0..60 {
+            for x in 0..80 {
+                let expected = exact.get_distance(x, y);
+                if expected.abs() < 7.0 {
+                    assert!((exact_u8.get_distance(x, y) - expected).abs() <= 0.5 * 8.0 / 126.0, "exact u8 at {}, {}", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn quantized_range_round_trips_symmetrically(){
+        let mut storage = U8DistanceStorage::<8>::new(4);
+        storage.set(0, 8.0);
+        storage.set(1, -8.0);
+        storage.set(2, 8.1);
+        storage.set(3, -8.1);
+
+        assert_eq!(storage.values, vec![ 254, 2, 255, 0 ]);
+        assert!((storage.get(0) - 8.0).abs() < 1e-5);
+        assert!((storage.get(1) + 8.0).abs() < 1e-5);
+        assert_eq!(storage.get(2), f32::INFINITY);
+        assert_eq!(storage.get(3), f32::NEG_INFINITY);
+
+        let mut storage = U16DistanceStorage::<8>::new(2);
+        storage.set(0, 8.0);
+        storage.set(1, -8.0);
+
+        assert_eq!(storage.values, vec![ 65534, 2 ]);
+        assert!((storage.get(0) - 8.0).abs() < 1e-5);
+        assert!((storage.get(1) + 8.0).abs() < 1e-5);
+    }
+
+    #[test]
+    pub fn quantized_field_with_distant_shape_is_not_empty(){
+        let binary_image = binary_image::of_function(200, 200, |x, y| x > 190 && y > 190);
+
+        assert!(SignedDistanceField::<F32DistanceStorage>::try_compute(&binary_image).is_ok());
+        assert!(SignedDistanceField::<U8DistanceStorage>::try_compute(&binary_image).is_ok());
+        assert!(SignedDistanceField::<U8DistanceStorage>::try_compute_exact(&binary_image).is_ok());
+
+        let empty = binary_image::of_function(200, 200, |_, _| false);
+        assert_eq!(SignedDistanceField::<U8DistanceStorage>::try_compute(&empty).err(), Some(Error::NoShapeFound));
+    }
+
+    #[test]
+    pub fn discarded_targets_keep_distances(){
+        let buffer = circle_image(64, 48, 15);
+        let binary_image = binary_image::of_byte_slice(&buffer, 64, 48);
+
+        let stored = SignedDistanceField::<F16DistanceStorage>::compute(&binary_image);
+        let discarded = SignedDistanceField::<F16DistanceStorage, DiscardedTargetStorage>::compute(&binary_image);
+        assert_eq!(stored.distances, discarded.distances);
+
+        let stored = SignedDistanceField::<F32DistanceStorage>::compute_exact(&binary_image);
+        let discarded = SignedDistanceField::<F32DistanceStorage, DiscardedTargetStorage<U32TargetStorage>>::compute_exact(&binary_image);
+        assert_eq!(stored.distances, discarded.distances);
+
+        let normalized = discarded.normalize_clamped_distances(-4.0, 4.0).unwrap();
+        assert_eq!(normalized.to_u8(), stored.normalize_clamped_distances(-4.0, 4.0).unwrap().to_u8());
+    }
+
+    #[test]
+    #[should_panic(expected = "Distance targets have been discarded")]
+    pub fn discarded_targets_are_not_available(){
+        let buffer = circle_image(16, 16, 5);
+        let binary_image = binary_image::of_byte_slice(&buffer, 16, 16);
+        let discarded = SignedDistanceField::<F32DistanceStorage, DiscardedTargetStorage>::compute(&binary_image);
+        discarded.get_distance_target(3, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Distance targets have been discarded")]
+    pub fn discarded_targets_cannot_be_updated(){
+        let buffer = circle_image(16, 16, 5);
+        let binary_image = binary_image::of_byte_slice(&buffer, 16, 16);
+
+        let mut discarded = SignedDistanceField::<F32DistanceStorage, DiscardedTargetStorage>::compute_exact(&binary_image);
+        discarded.compute_exact_into(&binary_image, &mut ScratchContext::default());
+        discarded.update_exact(&binary_image, Rectangle::new(2, 2, 3, 3));
+    }
+
+    #[test]
+    pub fn compute_into_reuses_storage(){
+        let images: Vec<(usize, usize, Vec<u8>)> = [ (40, 30, 10), (25, 50, 8), (40, 30, 5) ].iter()
+            .map(|&(width, height, radius)| (width, height, circle_image(width, height, radius)))
+            .collect();
+
+        let mut field = SignedDistanceField::<F32DistanceStorage>::compute(
+            &binary_image::of_byte_slice(&images[0].2, 40, 30)
+        );
+
+        let mut exact_field = field.clone();
+        let mut anti_aliased_field = field.clone();
+        let mut truncated_field = field.clone();
+        let mut scratch = ScratchContext::default();
+
+        for (width, height, buffer) in &images {
+            let binary_image = binary_image::of_byte_slice(buffer, *width as u32, *height as u32);
+
+            field.compute_into(&binary_image);
+            assert_eq!(field, SignedDistanceField::compute(&binary_image));
+
+            exact_field.compute_exact_into(&binary_image, &mut scratch);
+            assert_eq!(exact_field, SignedDistanceField::compute_exact(&binary_image));
+
+            anti_aliased_field.compute_anti_aliased_into(&binary_image, &mut scratch);
+            assert_eq!(anti_aliased_field, SignedDistanceField::compute_anti_aliased(&binary_image));
+
+            truncated_field.compute_truncated_into(&binary_image, 4.0, &mut scratch);
+            assert_eq!(truncated_field, SignedDistanceField::compute_truncated(&binary_image, 4.0));
+        }
+
+        // computing an image of the same size again must not reallocate
+        let distances = field.distances.as_ptr();
+        let targets = field.distance_targets.as_ptr();
+        field.compute_into(&binary_image::of_byte_slice(&images[0].2, 40, 30));
+        assert_eq!(distances, field.distances.as_ptr());
+        assert_eq!(targets, field.distance_targets.as_ptr());
+    }
+
+    #[test]
+    pub fn downscaled_distance_field_samples_exact_field(){
+        let width = 203;
+        let height = 150;
+
+        let is_inside = |x: usize, y: usize|
+            is_inside_circle(60, 70, 48)(x, y) || is_inside_rectangle(150, 30, 40, 20)(x, y);
+
+        let buffer: Vec<u8> = (0..width * height)
+            .map(|index| if is_inside(index % width, index / width) { 255 } else { 0 })
+            .collect();
+
+        let binary_image = binary_image::of_byte_slice(&buffer, width as u32, height as u32);
+        let full = SignedDistanceField::<F32DistanceStorage>::compute_exact(&binary_image);
+
+        let unscaled = SignedDistanceField::<F32DistanceStorage>::compute_downscaled(&binary_image, 1);
+        assert_eq!(unscaled, full);
+
+        let downscaled = SignedDistanceField::<F32DistanceStorage>::compute_downscaled(&binary_image, 4);
+        assert_eq!((downscaled.width, downscaled.height), (51, 38));
+
+        for y in 0..downscaled.height {
+            for x in 0..downscaled.width {
+                let (full_x, full_y) = ((x * 4 + 2).min(width as u32 - 1), (y * 4 + 2).min(height as u32 - 1));
+                let expected = full.get_distance(full_x, full_y) / 4.0;
+                assert!((downscaled.get_distance(x, y) - expected).abs() < 1e-5, "wrong distance at {}, {}", x, y);
+
+                let (target_x, target_y) = downscaled.get_distance_target(x, y);
+                assert!(target_x < downscaled.width && target_y < downscaled.height);
+            }
+        }
+
+        // the circle has a radius of 12 pixels in the downscaled field
+        let center_distance = downscaled.get_distance(15, 17);
+        let expected = ((15.0 * 4.0 + 2.0 - 60.0_f32).powi(2) + (17.0 * 4.0 + 2.0 - 70.0_f32).powi(2)).sqrt() / 4.0 - 12.0;
+        assert!((center_distance - expected).abs() < 0.5);
+    }
+
+    #[test]
+    pub fn unsigned_distance_field_measures_to_pixel_centers(){
+        let image = binary_image::of_function(40, 30, |x, y| (10..20).contains(&x) && (5..15).contains(&y));
+
+        let to_inside: SignedDistanceField<F32DistanceStorage> = SignedDistanceField::compute_unsigned(&image, PixelSet::Inside);
+        let to_outside: SignedDistanceField<F32DistanceStorage> = SignedDistanceField::compute_unsigned(&image, PixelSet::Outside);
+
+        for y in 0..image.height() {
+            for x in 0..image.width() {
+                let inside = image.is_inside(x, y);
+                let nearest = |set_is_inside: bool| (0..image.height())
+                    .flat_map(|set_y| (0..image.width()).map(move |set_x| (set_x, set_y)))
+                    .filter(|&(set_x, set_y)| image.is_inside(set_x, set_y) == set_is_inside)
+                    .map(|(set_x, set_y)| ((set_x as f32 - x as f32).powi(2) + (set_y as f32 - y as f32).powi(2)).sqrt())
+                    .fold(f32::INFINITY, f32::min);
+
+                assert_eq!(to_inside.get_distance(x, y) == 0.0, inside);
+                assert_eq!(to_outside.get_distance(x, y) == 0.0, !inside);
+                assert!((to_inside.get_distance(x, y) - nearest(true)).abs() < 1e-4);
+                assert!((to_outside.get_distance(x, y) - nearest(false)).abs() < 1e-4);
+
+                let (target_x, target_y) = to_inside.get_distance_target(x, y);
+                assert!(image.is_inside(target_x, target_y));
+
+                let (target_x, target_y) = to_outside.get_distance_target(x, y);
+                assert!(!image.is_inside(target_x, target_y));
+            }
+        }
+
+        // a pixel right next to the square is one pixel away from it, not zero as in the signed field
+        assert_eq!(to_inside.get_distance(9, 8), 1.0);
+        assert_eq!(to_outside.get_distance(10, 8), 1.0);
+        assert_eq!(to_outside.get_distance(14, 9), 5.0);
+
+        let empty = binary_image::of_function(8, 8, |_, _| false);
+        let empty: SignedDistanceField<F32DistanceStorage> = SignedDistanceField::compute_unsigned(&empty, PixelSet::Inside);
+        assert!(empty.distances.iter().all(|distance| distance.is_infinite()));
+    }
+
+    #[test]
+    pub fn truncated_distance_field_equals_clamped(){
+        let width = 200;
+        let height = 150;
+        let radius = 7.5;
+
+        let is_inside = |x: usize, y: usize|
+            is_inside_circle(40, 30, 12)(x, y) || is_inside_rectangle(150, 110, 30, 20)(x, y);
+
+        let buffer: Vec<u8> = (0..width * height)
+            .map(|index| if is_inside(index % width, index / width) { 255 } else { 0 })
+            .collect();
+
+        let binary_image = binary_image::of_byte_slice(&buffer, width as u32, height as u32);
+        let truncated = SignedDistanceField::<F32DistanceStorage>::compute_truncated(&binary_image, radius);
+        let full = SignedDistanceField::<F32DistanceStorage>::compute(&binary_image);
+
+        for y in 0..height as u32 {
+            for x in 0..width as u32 {
+                let expected = full.get_distance(x, y).max(-radius).min(radius);
+                assert_eq!(truncated.get_distance(x, y), expected, "wrong distance at {}, {}", x, y);
+            }
+        }
+
+        let empty = vec![0_u8; width * height];
+        let empty = binary_image::of_byte_slice(&empty, width as u32, height as u32);
+        let empty = SignedDistanceField::<F32DistanceStorage>::compute_truncated(&empty, radius);
+        assert_eq!(empty.get_distance(100, 75), radius);
+    }
+
+    #[test]
+    pub fn truncated_distance_field_with_negative_radius(){
+        let width = 64;
+        let height = 48;
+
+        let buffer: Vec<u8> = (0..width * height)
+            .map(|index| if is_inside_circle(30, 20, 12)(index % width, index / width) { 255 } else { 0 })
+            .collect();
+
+        let binary_image = binary_image::of_byte_slice(&buffer, width as u32, height as u32);
+        let negative = SignedDistanceField::<F32DistanceStorage>::compute_truncated(&binary_image, -3.0);
+        let zero = SignedDistanceField::<F32DistanceStorage>::compute_truncated(&binary_image, 0.0);
+        assert_eq!(negative, zero);
+
+        for y in 0..height as u32 {
+            for x in 0..width as u32 {
+                let distance = negative.get_distance(x, y);
+                assert_eq!(distance.abs(), 0.0, "distance is not saturated at {}, {}", x, y);
+                assert_eq!(distance.is_sign_negative(), binary_image.is_inside(x, y), "wrong sign at {}, {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    pub fn updated_distance_field_equals_recomputed(){
+        let width = 61;
+        let height = 47;
+
+        let mut buffer: Vec<u8> = (0..width * height)
+            .map(|index| if is_inside_circle(20, 25, 12)(index % width, index / width) { 255 } else { 0 })
+            .collect();
+
+        let mut field = SignedDistanceField::<F32DistanceStorage>::compute_exact(
+            &binary_image::of_byte_slice(&buffer, width as u32, height as u32)
+        );
+
+        let strokes = [
+            (Rectangle::new(40, 5, 6, 9), 255), // add a new shape far away
+            (Rectangle::new(14, 20, 4, 30), 0), // cut the circle in half
+            (Rectangle::new(0, 0, 61, 47), 0), // erase everything
+            (Rectangle::new(58, 44, 10, 10), 255), // paint a dot partially outside of the image
+            (Rectangle::new(30, 30, 0, 5), 255), // change nothing
+        ];
+
+        for &(stroke, value) in &strokes {
+            for y in 0..height as u32 {
+                for x in 0..width as u32 {
+                    if stroke.contains(x, y) { buffer[y as usize * width + x as usize] = value; }
+                }
+            }
+
+            let binary_image = binary_image::of_byte_slice(&buffer, width as u32, height as u32);
+            field.update_exact(&binary_image, stroke);
+
+            let recomputed = SignedDistanceField::<F32DistanceStorage>::compute_exact(&binary_image);
+
+            for y in 0..height as u32 {
+                for x in 0..width as u32 {
+                    let distance = field.get_distance(x, y);
+                    assert_eq!(distance, recomputed.get_distance(x, y), "{:?}: wrong distance at {}, {}", stroke, x, y);
+                    assert_eq!(distance.is_sign_negative(), recomputed.get_distance(x, y).is_sign_negative());
+
+                    if distance.is_finite() {
+                        let (target_x, target_y) = field.get_distance_target(x, y);
+                        assert_eq!(recomputed.get_distance(target_x, target_y).abs(), 0.0, "target is not an edge");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn updated_distance_field_only_visits_nearby_pixels(){
+        let width = 200;
+        let height = 200;
+
+        let mut buffer: Vec<u8> = (0..width * height)
+            .map(|index| if is_inside_circle(30, 30, 10)(index % width, index / width) { 255 } else { 0 })
+            .collect();
+
+        let mut field = SignedDistanceField::<F32DistanceStorage>::compute_exact(
+            &binary_image::of_byte_slice(&buffer, width as u32, height as u32)
+        );
+
+        // a far away pixel, which would be recomputed if it was visited
+        let far_away = field.flatten_index(190, 190);
+        field.distances.set(far_away, 1000.0);
+
+        // cut a hole into the circle
+        let stroke = Rectangle::new(28, 28, 3, 3);
+        for y in 28 .. 31 { for x in 28 .. 31 { buffer[y * width + x] = 0; } }
+
+        let binary_image = binary_image::of_byte_slice(&buffer, width as u32, height as u32);
+        field.update_exact(&binary_image, stroke);
+
+        let recomputed = SignedDistanceField::<F32DistanceStorage>::compute_exact(&binary_image);
+        assert_eq!(field.get_distance(190, 190), 1000.0);
+
+        for y in 0..height as u32 {
+            for x in 0..width as u32 {
+                if (x, y) != (190, 190) {
+                    assert_eq!(field.get_distance(x, y), recomputed.get_distance(x, y), "wrong distance at {}, {}", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn wrapped_distance_field_tiles_seamlessly(){
+        let width = 48;
+        let height = 40;
+
+        // a circle around the top left corner, which is split across all four borders
+        let is_inside = |x: usize, y: usize| {
+            let wrapped_x = x.min(width - x) as f32;
+            let wrapped_y = y.min(height - y) as f32;
+            (wrapped_x * wrapped_x + wrapped_y * wrapped_y).sqrt() < 12.0
+        };
+
+        let binary_image_buffer: Vec<u8> = (0..width * height)
+            .map(|index| if is_inside(index % width, index / width) { 255 } else { 0 })
+            .collect();
+
+        let binary_image = binary_image::of_byte_slice(
+            &binary_image_buffer, width as u32, height as u32
+        );
+
+        for &wrap in &[ Wrap::Horizontal, Wrap::Vertical, Wrap::Both ] {
+            let wrap_x = |x: isize| if wrap.wraps_x() { Some((x + width as isize) as usize % width) }
+                else if x >= 0 && x < width as isize { Some(x as usize) } else { None };
+
+            let wrap_y = |y: isize| if wrap.wraps_y() { Some((y + height as isize) as usize % height) }
+                else if y >= 0 && y < height as isize { Some(y as usize) } else { None };
+
+            let is_edge = |x: usize, y: usize| {
+                let neighbours = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+                neighbours.iter().any(|&(neighbour_x, neighbour_y)| {
+                    match (wrap_x(x as isize + neighbour_x), wrap_y(y as isize + neighbour_y)) {
+                        (Some(neighbour_x), Some(neighbour_y)) => is_inside(x, y) != is_inside(neighbour_x, neighbour_y),
+                        _ => false,
+                    }
+                })
+            };
+
+            let edges: Vec<(usize, usize)> = (0..width * height)
+                .map(|index| (index % width, index / width))
+                .filter(|&(x, y)| is_edge(x, y))
+                .collect();
+
+            let wrapped = SignedDistanceField::<F32DistanceStorage>::compute_wrapped(&binary_image, wrap);
+            let mut total_error = 0.0;
+
+            for y in 0..height {
+                for x in 0..width {
+                    let nearest_edge = edges.iter()
+                        .map(|&(edge_x, edge_y)| {
+                            let difference_x = (x as f32 - edge_x as f32).abs();
+                            let difference_y = (y as f32 - edge_y as f32).abs();
+                            let difference_x = if wrap.wraps_x() { difference_x.min(width as f32 - difference_x) } else { difference_x };
+                            let difference_y = if wrap.wraps_y() { difference_y.min(height as f32 - difference_y) } else { difference_y };
+                            (difference_x * difference_x + difference_y * difference_y).sqrt()
+                        })
+                        .fold(f32::INFINITY, f32::min);
+
+                    let distance = wrapped.get_distance(x as u32, y as u32);
+                    assert_eq!(distance.is_sign_negative(), is_inside(x, y), "wrong distance sign");
+
+                    // dead reckoning only approximates the distances
+                    let error = (distance.abs() - nearest_edge).abs();
+                    total_error += error;
+
+                    assert!(
+                        error < 1.0,
+                        "{:?}: distance at {}, {} should be {} but was {}", wrap, x, y, nearest_edge, distance
+                    );
+                }
+            }
+
+            let error_per_pixel = total_error / (width * height) as f32;
+            assert!(error_per_pixel < 0.01, "{:?}: average error per pixel is {}", wrap, error_per_pixel);
+
+            let mut reused = SignedDistanceField::<F32DistanceStorage>::compute(&binary_image::of_function(3, 5, |x, _| x == 1));
+            reused.compute_wrapped_into(&binary_image, wrap);
+            assert_eq!(reused, wrapped);
+        }
+
+        let unwrapped = SignedDistanceField::<F32DistanceStorage>::compute(&binary_image);
+        let wrapped_none = SignedDistanceField::<F32DistanceStorage>::compute_wrapped(&binary_image, Wrap::None);
+        assert_eq!(unwrapped, wrapped_none);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    pub fn parallel_exact_distance_field_equals_serial(){
+        let width = 531;
+        let height = 379;
+        let is_inside = is_inside_checker(71, 43);
+
+        let binary_image_buffer: Vec<u8> = (0..width * height)
+            .map(|index| if is_inside(index % width, index / width) { 255 } else { 0 })
+            .collect();
+
+        let binary_image = binary_image::of_byte_slice(
+            &binary_image_buffer, width as u32, height as u32
+        );
+
+        let serial = SignedDistanceField::<F16DistanceStorage>::compute_exact(&binary_image);
+        let parallel = SignedDistanceField::<F16DistanceStorage>::compute_exact_parallel(&binary_image);
+        assert_eq!(serial, parallel);
+
+        let empty_buffer = vec![0_u8; width * height];
+        let empty_image = binary_image::of_byte_slice(&empty_buffer, width as u32, height as u32);
+        let serial = SignedDistanceField::<F32DistanceStorage>::compute_exact(&empty_image);
+        let parallel = SignedDistanceField::<F32DistanceStorage>::compute_exact_parallel(&empty_image);
+        assert_eq!(serial, parallel);
+
+        let mut scratch = ScratchContext::default();
+        let mut reused = SignedDistanceField::<U8DistanceStorage, DiscardedTargetStorage>::compute_exact(&empty_image);
+        reused.compute_exact_parallel_into(&binary_image, &mut scratch);
+
+        let serial = SignedDistanceField::<U8DistanceStorage, DiscardedTargetStorage>::compute_exact(&binary_image);
+        assert_eq!(serial.distances, reused.distances);
+    }
+
+    #[test]
+    pub fn anti_aliased_circle_distance_field(){
+        let width = 512;
+        let height = 512;
+        let distance = circle_distance(200, 230, 137);
+
+        // compute the coverage of each pixel by supersampling
+        let samples = 8;
+        let coverage_buffer: Vec<u8> = (0..width * height).map(|index| {
+            let (x, y) = (index % width, index / width);
+            let inside_samples = (0..samples * samples).filter(|sample| {
+                let sample_x = x as f32 + ((sample % samples) as f32 + 0.5) / samples as f32 - 0.5;
+                let sample_y = y as f32 + ((sample / samples) as f32 + 0.5) / samples as f32 - 0.5;
+                ((sample_x - 200.0).powi(2) + (sample_y - 230.0).powi(2)).sqrt() < 137.0
+            }).count();
+
+            (inside_samples * 255 / (samples * samples)) as u8
+        }).collect();
+
+        let image = binary_image::of_byte_slice(&coverage_buffer, width as u32, height as u32);
+        let anti_aliased = SignedDistanceField::<F32DistanceStorage>::compute_anti_aliased(&image);
+        let binary = compute_f32_distance_field(&image);
+
+        let mut anti_aliased_error = 0.0;
+        let mut binary_error = 0.0;
+        let mut pixels = 0;
+
+        for y in 0..height {
+            for x in 0..width {
+                let expected = distance(x, y);
+                let anti_aliased = anti_aliased.get_distance(x as u32, y as u32);
+
+                assert_eq!(anti_aliased < 0.0, image.is_inside(x as u32, y as u32), "wrong sign at {}, {}", x, y);
+
+                // only the pixels near the edge are affected by anti-aliasing
+                if expected.abs() < 8.0 {
+                    anti_aliased_error += (anti_aliased - expected).abs();
+                    binary_error += (binary.get_distance(x as u32, y as u32) - expected).abs();
+                    pixels += 1;
+                }
+            }
+        }
+
+        let anti_aliased_error = anti_aliased_error / pixels as f32;
+        let binary_error = binary_error / pixels as f32;
+
+        assert!(anti_aliased_error < 0.15, "average error per pixel is {}", anti_aliased_error);
+        assert!(
+            anti_aliased_error < binary_error / 4.0,
+            "average error per pixel {} is not much lower than {} of the binary image", anti_aliased_error, binary_error
+        );
+    }
+
+    #[test]
+    pub fn coverage_follows_custom_threshold(){
+        let bytes: Vec<u8> = (0 ..= 255).collect();
+
+        for &threshold in &[0, 40, 127, 200, 254, 255] {
+            let image = binary_image::of_byte_slice_with_threshold(&bytes, 256, 1, threshold);
+            assert_eq!(image.coverage(0, 0), 0.0);
+
+            for x in 1 .. 256 {
+                let coverage = image.coverage(x, 0);
+                assert!(coverage > image.coverage(x - 1, 0), "coverage is not increasing");
+                assert_eq!(coverage > 0.5, image.is_inside(x, 0), "coverage disagrees with threshold {} at {}", threshold, x);
+            }
+
+            if threshold != 255 { assert_eq!(image.coverage(255, 0), 1.0); }
+
+            // the sub-pixel edges lie between the pixels which are inside and outside
+            let ramp: Vec<u8> = (0 .. 64).map(|x| (x * 4) as u8).collect();
+            let image = binary_image::of_byte_slice_with_threshold(&ramp, 64, 1, threshold);
+            let field = SignedDistanceField::<F32DistanceStorage>::compute_anti_aliased(&image);
+
+            for x in 0 .. 64 {
+                let distance = field.get_distance(x, 0);
+                if distance.is_finite() {
+                    assert_eq!(distance < 0.0, image.is_inside(x, 0), "wrong sign at {} with threshold {}", x, threshold);
+                }
+            }
+        }
+
+        let image = binary_image::of_byte_slice(&bytes, 256, 1);
+        for x in 0 .. 256 {
+            assert_eq!(image.coverage(x, 0), x as f32 / 255.0);
+        }
+    }
+
+    #[test]
+    pub fn wide_image_distance_field(){
+        let width = 70_000;
+        let height = 3;
+
+        // a single inside pixel at the far right end
+        let mut binary_image_buffer = vec![0_u8; width * height];
+        binary_image_buffer[width + width - 2] = 255;
+
+        let binary_image = binary_image::of_byte_slice(&binary_image_buffer, width as u32, height as u32);
+        let approximated: SignedDistanceField<F32DistanceStorage, U32TargetStorage> = SignedDistanceField::compute(&binary_image);
+        let exact: SignedDistanceField<F32DistanceStorage, U32TargetStorage> = SignedDistanceField::compute_exact(&binary_image);
+
+        for distance_field in &[approximated, exact] {
+            assert_eq!(distance_field.get_distance(0, 1), (width - 3) as f32);
+            assert_eq!(distance_field.get_distance_target(0, 1), (width as u32 - 3, 1));
+            assert_eq!(distance_field.get_distance(width as u32 - 2, 1), -0.0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Image too large for target storage")]
+    pub fn wide_image_does_not_fit_u16_targets(){
+        let width = 70_000;
+        let binary_image_buffer = vec![0_u8; width];
+        let binary_image = binary_image::of_byte_slice(&binary_image_buffer, width as u32, 1);
+        compute_f32_distance_field(&binary_image);
+    }
+
+    #[test]
+    pub fn wide_image_reports_error(){
+        let width = 70_000;
+        let mut binary_image_buffer = vec![0_u8; width];
+        binary_image_buffer[3] = 255;
+        let binary_image = binary_image::of_byte_slice(&binary_image_buffer, width as u32, 1);
+
+        let too_large = Some(Error::DimensionTooLarge { side: width as u32, max_side: 65536 });
+        assert_eq!(SignedDistanceField::<F32DistanceStorage>::try_compute(&binary_image).err(), too_large);
+        assert_eq!(SignedDistanceField::<F32DistanceStorage>::try_compute_anti_aliased(&binary_image).err(), too_large);
+        assert_eq!(SignedDistanceField::<F32DistanceStorage>::try_compute_exact(&binary_image).err(), too_large);
+        assert_eq!(SignedDistanceField::<F32DistanceStorage>::try_compute_wrapped(&binary_image, Wrap::Both).err(), too_large);
+
+        #[cfg(feature = "parallel")]
+        assert_eq!(SignedDistanceField::<F32DistanceStorage>::try_compute_exact_parallel(&binary_image).err(), too_large);
+
+        assert!(SignedDistanceField::<F32DistanceStorage, U32TargetStorage>::try_compute_exact(&binary_image).is_ok());
+        assert!(SignedDistanceField::<F32DistanceStorage, U32TargetStorage>::try_compute_wrapped(&binary_image, Wrap::Horizontal).is_ok());
+
+        #[cfg(feature = "parallel")]
+        assert!(SignedDistanceField::<F32DistanceStorage, U32TargetStorage>::try_compute_exact_parallel(&binary_image).is_ok());
+    }
+
+    #[test]
+    pub fn shape_distance_field_is_exact(){
+        let circle = PathBuilder::new()
+            .move_to((60.0, 32.0))
+            .cubic_to((60.0, 47.5), (47.5, 60.0), (32.0, 60.0))
+            .cubic_to((16.5, 60.0), (4.0, 47.5), (4.0, 32.0))
+            .cubic_to((4.0, 16.5), (16.5, 4.0), (32.0, 4.0))
+            .cubic_to((47.5, 4.0), (60.0, 16.5), (60.0, 32.0))
+            .build();
+
+        let rectangle = Shape::new(vec![
+            Contour::polygon(&[ (70.0, 10.0), (120.0, 10.0), (120.0, 50.0), (70.0, 50.0) ])
+        ]);
+
+        let expected_circle = |x: f32, y: f32| ((x - 32.0).powi(2) + (y - 32.0).powi(2)).sqrt() - 28.0;
+        let expected_rectangle = |x: f32, y: f32| {
+            let x = (x - 95.0).abs() - 25.0;
+            let y = (y - 30.0).abs() - 20.0;
+            (x.max(0.0).powi(2) + y.max(0.0).powi(2)).sqrt() + x.max(y).min(0.0)
+        };
+
+        let mut shape = circle;
+        shape.contours.extend(rectangle.contours);
+
+        let field = SignedDistanceField::<F32DistanceStorage>::compute_shape(&shape, 128, 64, FillRule::NonZero);
+
+        for y in 0..64 {
+            for x in 0..128 {
+                let (center_x, center_y) = (x as f32 + 0.5, y as f32 + 0.5);
+                let expected = expected_circle(center_x, center_y).min(expected_rectangle(center_x, center_y));
+                let distance = field.get_distance(x, y);
+
+                // the cubic curves only approximate a circle
+                assert!((distance - expected).abs() < 0.05, "wrong distance {} at {}, {}", distance, x, y);
+
+                let (target_x, target_y) = field.get_distance_target(x, y);
+                let target_distance = expected_circle(target_x as f32 + 0.5, target_y as f32 + 0.5).abs()
+                    .min(expected_rectangle(target_x as f32 + 0.5, target_y as f32 + 0.5).abs());
+
+                assert!(target_distance <= 1.0, "target {}, {} is not at the outline", target_x, target_y);
+            }
+        }
+    }
+
+    #[test]
+    pub fn shape_distance_field_finds_nearest_point_on_curves(){
+        // a cubic curve with a loop, which has many local minima of the distance
+        let curve = Segment::Cubic((4.0, 28.0), (60.0, -20.0), (-28.0, -20.0), (28.0, 28.0));
+        let shape = Shape::new(vec![ Contour::new(vec![ curve, Segment::Line((28.0, 28.0), (4.0, 28.0)) ]) ]);
+
+        let field = SignedDistanceField::<F32DistanceStorage>::compute_shape(&shape, 32, 32, FillRule::NonZero);
+
+        let curve_point = |t: f64| {
+            let (x, y) = curve.point(t as f32);
+            (x as f64, y as f64)
+        };
+
+        for y in 0..32 {
+            for x in 0..32 {
+                let (center_x, center_y) = (x as f64 + 0.5, y as f64 + 0.5);
+                let distance_to = |t: f64| {
+                    let (curve_x, curve_y) = curve_point(t);
+                    ((curve_x - center_x).powi(2) + (curve_y - center_y).powi(2)).sqrt()
+                };
+
+                // search the nearest point coarsely, then refine it around the nearest sample
+                let coarse = (0 ..= 1000).map(|sample| sample as f64 / 1000.0)
+                    .fold(0.0, |nearest, t| if distance_to(t) < distance_to(nearest) { t } else { nearest });
+
+                let curve_distance = (0 ..= 2000).map(|sample| (coarse + (sample as f64 - 1000.0) / 1_000_000.0).clamp(0.0, 1.0))
+                    .map(distance_to).fold(f64::INFINITY, f64::min);
+
+                let line_distance = if center_x < 4.0 { ((4.0 - center_x).powi(2) + (center_y - 28.0).powi(2)).sqrt() }
+                    else if center_x > 28.0 { ((center_x - 28.0).powi(2) + (center_y - 28.0).powi(2)).sqrt() }
+                    else { (center_y - 28.0).abs() };
+
+                let expected = curve_distance.min(line_distance) as f32;
+                let distance = field.get_distance(x, y).abs();
+                assert!((distance - expected).abs() < 1e-4, "wrong distance {} instead of {} at {}, {}", distance, expected, x, y);
+            }
+        }
+    }
+
+    #[test]
+    pub fn shape_distance_field_fill_rules(){
+        // two overlapping squares with the same orientation
+        let shape = Shape::new(vec![
+            Contour::polygon(&[ (4.0, 4.0), (20.0, 4.0), (20.0, 20.0), (4.0, 20.0) ]),
+            Contour::polygon(&[ (12.0, 12.0), (28.0, 12.0), (28.0, 28.0), (12.0, 28.0) ]),
+        ]);
+
+        let non_zero = SignedDistanceField::<F32DistanceStorage>::compute_shape(&shape, 32, 32, FillRule::NonZero);
+        let even_odd = SignedDistanceField::<F32DistanceStorage>::compute_shape(&shape, 32, 32, FillRule::EvenOdd);
+
+        assert!(non_zero.get_distance(16, 16) < 0.0);
+        assert!(even_odd.get_distance(16, 16) > 0.0);
+
+        assert!(non_zero.get_distance(8, 8) < 0.0);
+        assert!(even_odd.get_distance(8, 8) < 0.0);
+
+        assert!(non_zero.get_distance(1, 30) > 0.0);
+        assert!(even_odd.get_distance(1, 30) > 0.0);
+        assert_eq!(non_zero.get_distance(1, 30), even_odd.get_distance(1, 30));
+    }
+
+    #[test]
+    pub fn empty_shape_distance_field_is_infinite(){
+        let field = SignedDistanceField::<F16DistanceStorage>::compute_shape(&Shape::default(), 8, 8, FillRule::NonZero);
+        assert!(field.get_distance(3, 3).is_infinite());
+        assert!(field.normalize_distances().is_none());
+    }
+
+    #[test]
+    pub fn byte_slice_dimension_mismatch(){
+        let buffer = vec![0_u8; 12];
+
+        assert_eq!(
+            binary_image::try_of_byte_slice(&buffer, 4, 4).err(),
+            Some(Error::DimensionMismatch { expected: 16, actual: 12 })
+        );
+
+        assert_eq!(binary_image::try_of_byte_slice(&[], 0, 4).err(), Some(Error::EmptyImage));
+        assert!(binary_image::try_of_byte_slice(&buffer, 4, 3).is_ok());
+    }
+
+    #[test]
+    pub fn errors_distinguish_normalization_failures(){
+        let empty = vec![0_u8; 16];
+        let empty = binary_image::try_of_byte_slice(&empty, 4, 4).unwrap();
+
+        assert_eq!(
+            SignedDistanceField::<F32DistanceStorage>::try_compute(&empty).err(),
+            Some(Error::NoShapeFound)
+        );
+
+        assert_eq!(
+            SignedDistanceField::<F32DistanceStorage>::compute(&empty).try_normalize_distances().err(),
+            Some(Error::NoShapeFound)
+        );
+
+        let mut dot = vec![0_u8; 16];
+        dot[5] = 255;
+        let dot = binary_image::try_of_byte_slice(&dot, 4, 4).unwrap();
+        let field = SignedDistanceField::<F32DistanceStorage>::try_compute_exact(&dot).unwrap();
+
+        assert_eq!(field.clone().try_normalize_clamped_distances(1.0, 1.0).err(), Some(Error::ZeroSizeRange));
+        assert!(field.try_normalize_clamped_distances(-1.0, 1.0).is_ok());
+
+        // every pixel is at an edge, so all distances are zero
+        let checker = [255, 0, 0, 255];
+        let checker = binary_image::try_of_byte_slice(&checker, 2, 2).unwrap();
+        let field = SignedDistanceField::<F32DistanceStorage>::try_compute(&checker).unwrap();
+        assert_eq!(field.try_normalize_distances().err(), Some(Error::ZeroSizeRange));
+    }
+
     pub fn reconstruct_distance_field(
         width: usize, height: usize, tolerance: f32,
         image: impl Fn(usize, usize) -> f32
+    ) {
+        reconstruct_distance_field_with(
+            width, height, tolerance, image,
+            |image| compute_f32_distance_field(image),
+            |image| compute_f16_distance_field(image),
+        )
+    }
+
+    pub fn reconstruct_exact_distance_field(
+        width: usize, height: usize, tolerance: f32,
+        image: impl Fn(usize, usize) -> f32
+    ) {
+        reconstruct_distance_field_with(
+            width, height, tolerance, image,
+            |image| compute_exact_distance_field(image),
+            |image| compute_exact_distance_field(image),
+        )
+    }
+
+    pub fn reconstruct_distance_field_with(
+        width: usize, height: usize, tolerance: f32,
+        image: impl Fn(usize, usize) -> f32,
+        compute_32: impl Fn(&binary_image::BinaryByteSliceImage<'_>) -> SignedDistanceField<F32DistanceStorage>,
+        compute_16: impl Fn(&binary_image::BinaryByteSliceImage<'_>) -> SignedDistanceField<F16DistanceStorage>,
     ) {
         let mut distance_buffer = vec![0.0; width * height];
 
@@ -220,15 +1178,15 @@ mod tests {
             .collect();
 
         let binary_image = binary_image::of_byte_slice(
-            &binary_image_buffer, width as u16, height as u16
+            &binary_image_buffer, width as u32, height as u32
         );
 
-        let distance_field_16 = compute_f32_distance_field(&binary_image);
-        let distance_field_32 = compute_f16_distance_field(&binary_image);
+        let distance_field_16 = compute_16(&binary_image);
+        let distance_field_32 = compute_32(&binary_image);
 
         let mut summed_error = 0.0;
-        for y in 0..height as u16 {
-            for x in 0..width as u16 {
+        for y in 0..height as u32 {
+            for x in 0..width as u32 {
                 let ground_truth = distance_buffer[y as usize * width + x as usize];
                 let reconstructed_16 = distance_field_16.get_distance(x, y);
                 let reconstructed_32 = distance_field_32.get_distance(x, y);
@@ -0,0 +1,119 @@
+//! Morphological operations on binary images, using the exact signed distance field.
+//! In contrast to kernel based operations, the duration does not depend on the radius.
+//! The radius is measured like the distances of `SignedDistanceField::compute_unsigned`,
+//! from the center of a pixel to the center of the nearest pixel inside or outside of the shapes,
+//! so that a radius of one adds or removes a single layer of pixels.
+
+use crate::binary_image::{ BinaryImage, BinaryBoolVecImage };
+use crate::distance_field::{ SignedDistanceField, F32DistanceStorage, DiscardedTargetStorage, PixelSet };
+
+/// Grows the shapes of the image by the radius.
+/// Pixels outside of the shapes are added if their distance to the nearest inside pixel is at most the radius.
+pub fn dilate(image: &impl BinaryImage, radius: f32) -> BinaryBoolVecImage {
+    threshold(image, PixelSet::Inside, |distance| distance <= radius)
+}
+
+/// Shrinks the shapes of the image by the radius.
+/// Pixels inside of the shapes are removed if their distance to the nearest outside pixel is at most the radius.
+pub fn erode(image: &impl BinaryImage, radius: f32) -> BinaryBoolVecImage {
+    threshold(image, PixelSet::Outside, |distance| distance > radius)
+}
+
+/// Removes all parts of the shapes which are thinner than twice the radius,
+/// and rounds convex corners, by eroding and then dilating the image.
+pub fn open(image: &impl BinaryImage, radius: f32) -> BinaryBoolVecImage {
+    dilate(&erode(image, radius), radius)
+}
+
+/// Fills all gaps between the shapes which are narrower than twice the radius,
+/// and rounds concave corners, by dilating and then eroding the image.
+pub fn close(image: &impl BinaryImage, radius: f32) -> BinaryBoolVecImage {
+    erode(&dilate(image, radius), radius)
+}
+
+/// Rounds both the convex and the concave corners of the shapes with the radius,
+/// by closing and then opening the image.
+pub fn round_corners(image: &impl BinaryImage, radius: f32) -> BinaryBoolVecImage {
+    open(&close(image, radius), radius)
+}
+
+/// Computes the distance from every pixel to the nearest pixel of the set
+/// and selects all pixels for which the predicate of that distance is true.
+fn threshold(image: &impl BinaryImage, set: PixelSet, predicate: impl Fn(f32) -> bool) -> BinaryBoolVecImage {
+    let distance_field = SignedDistanceField::<F32DistanceStorage, DiscardedTargetStorage>::compute_unsigned(image, set);
+
+    crate::binary_image::of_function(image.width(), image.height(), |x, y| {
+        predicate(distance_field.get_distance(x, y))
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn square(size: u32, min: u32, max: u32) -> binary_image::BinaryBoolVecImage {
+        binary_image::of_function(size, size, |x, y| (min .. max).contains(&x) && (min .. max).contains(&y))
+    }
+
+    #[test]
+    pub fn dilate_and_erode_whole_layers(){
+        let image = square(20, 8, 12);
+
+        assert_eq!(morphology::dilate(&image, 0.0), image);
+        assert_eq!(morphology::erode(&image, 0.0), image);
+
+        // the diagonal pixels at the corners are further away than one pixel
+        let is_corner = |x: u32, y: u32| (x == 7 || x == 12) && (y == 7 || y == 12);
+        let dilated = binary_image::of_function(20, 20, |x, y| square(20, 7, 13).is_inside(x, y) && !is_corner(x, y));
+        assert_eq!(morphology::dilate(&image, 1.0), dilated);
+        assert_eq!(morphology::erode(&image, 1.0), square(20, 9, 11));
+        assert_eq!(morphology::erode(&image, 2.0), square(20, 0, 0));
+    }
+
+    #[test]
+    pub fn dilation_is_round(){
+        let image = binary_image::of_function(41, 41, |x, y| x == 20 && y == 20);
+        let dilated = morphology::dilate(&image, 10.0);
+
+        for y in 0..41 {
+            for x in 0..41 {
+                let distance = ((x as f32 - 20.0).powi(2) + (y as f32 - 20.0).powi(2)).sqrt();
+                assert_eq!(dilated.is_inside(x, y), distance <= 10.0, "{}, {}", x, y);
+            }
+        }
+
+        let eroded = morphology::erode(&dilated, 10.0);
+        assert_eq!(eroded, image);
+    }
+
+    #[test]
+    pub fn open_removes_thin_lines_and_close_fills_gaps(){
+        let line = binary_image::of_function(30, 30, |x, y| (5..25).contains(&x) && y == 15);
+        assert!(morphology::open(&line, 2.0).pixels.iter().all(|&pixel| !pixel));
+
+        let square = square(30, 5, 25);
+        let opened = morphology::open(&square, 2.0);
+        assert!(opened.is_inside(15, 5) && opened.is_inside(5, 15) && opened.is_inside(15, 15));
+        assert!(!opened.is_inside(5, 5));
+
+        let split = binary_image::of_function(30, 30, |x, y| square.is_inside(x, y) && x != 15);
+
+        // the ends of the gap stay notched, as the dilated edge is rounded around them
+        let is_notch = |x: u32, y: u32| x == 15 && (y == 5 || y == 24);
+        let closed = binary_image::of_function(30, 30, |x, y| square.is_inside(x, y) && !is_notch(x, y));
+        assert_eq!(morphology::close(&split, 2.0), closed);
+    }
+
+    #[test]
+    pub fn round_corners_keeps_straight_edges(){
+        let image = square(30, 5, 25);
+        let rounded = morphology::round_corners(&image, 4.0);
+
+        assert!(!rounded.is_inside(5, 5));
+        assert!(!rounded.is_inside(24, 24));
+        assert!(rounded.is_inside(15, 5));
+        assert!(rounded.is_inside(5, 15));
+        assert!(rounded.is_inside(15, 15));
+    }
+}
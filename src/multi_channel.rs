@@ -0,0 +1,541 @@
+use crate::distance_field::{ DistanceStorage, check_not_empty };
+use crate::shape::{ Shape, Segment, SegmentDistance, Vector };
+use crate::error::{ Error, Result };
+
+
+/// Contains three distance channels per pixel, produced by `MultiChannelDistanceField::compute`.
+/// The median of the three channels is the signed distance to the shape,
+/// but in contrast to a single channel distance field,
+/// corners of the shape can be reconstructed sharply from the median of interpolated channels.
+/// Can be normalized in order to convert to an RGB image with limited range.
+///
+/// The algorithm used is based on the thesis "Shape Decomposition for Multi-channel Distance Fields"
+/// by Viktor Chlumský, 2015.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MultiChannelDistanceField<D: DistanceStorage> {
+    pub width: u32,
+    pub height: u32,
+
+    /// A row-major image vector with
+    /// for each pixel three consecutive signed distances,
+    /// the red, green and blue channel.
+    pub distances: D,
+}
+
+/// Represents a multi-channel distance field which was normalized to the range `[0, 1]`.
+pub struct NormalizedMultiChannelDistanceField<D: DistanceStorage> {
+    pub width: u32,
+    pub height: u32,
+
+    /// A row-major image vector with three consecutive channels per pixel.
+    /// All distances are in the range of `[0..1]`.
+    pub distances: D,
+
+    /// In the original distance field, edges are represented by a distance of zero.
+    /// This float describes the new value that edges in the normalized field have.
+    pub zero_distance: f32,
+}
+
+
+/// Each edge of the shape is assigned a combination of the three channels.
+/// Edges meeting at a corner never share more than one channel.
+type EdgeColor = u8;
+
+const RED: EdgeColor = 1;
+const GREEN: EdgeColor = 2;
+const BLUE: EdgeColor = 4;
+const YELLOW: EdgeColor = RED | GREEN;
+const MAGENTA: EdgeColor = RED | BLUE;
+const CYAN: EdgeColor = GREEN | BLUE;
+const WHITE: EdgeColor = RED | GREEN | BLUE;
+
+/// The default angle threshold in radians, the same as the default of msdfgen.
+/// Like in msdfgen, only the sine of this angle is compared against the direction change,
+/// so `3.0` radians treats any direction change larger than about 8 degrees as a corner.
+pub const DEFAULT_CORNER_ANGLE: f32 = 3.0;
+
+
+impl<D> MultiChannelDistanceField<D> where D: DistanceStorage {
+
+    /// Computes the multi-channel distance field of the specified shape,
+    /// considering all segment connections with a direction change larger than about 8 degrees corners,
+    /// as specified by `DEFAULT_CORNER_ANGLE`.
+    /// The shape coordinates are measured in pixels of the distance field.
+    pub fn compute(shape: &Shape, width: u32, height: u32) -> Self {
+        Self::compute_with_corner_angle(shape, width, height, DEFAULT_CORNER_ANGLE)
+    }
+
+    /// Computes the multi-channel distance field of the specified shape, like `compute`.
+    /// Returns an error if the distance field has no pixels or the shape does not contain any segments.
+    pub fn try_compute(shape: &Shape, width: u32, height: u32) -> Result<Self> {
+        check_not_empty(width, height)?;
+
+        let distance_field = Self::compute(shape, width, height);
+        let channel_count = width as usize * height as usize * 3;
+
+        // quantized storages saturate distant pixels to infinity, so look for any finite distance
+        if (0..channel_count).any(|index| distance_field.distances.get(index).is_finite()) { Ok(distance_field) }
+        else { Err(Error::NoShapeFound) }
+    }
+
+    /// Computes the multi-channel distance field of the specified shape.
+    /// Segment connections are treated as sharp corners if the direction changes by more than 90 degrees,
+    /// or if the sine of the direction change is larger than the sine of `corner_angle`.
+    /// This is the angle threshold of msdfgen, so an angle above 90 degrees
+    /// behaves like the smaller angle with the same sine.
+    /// The shape coordinates are measured in pixels of the distance field.
+    pub fn compute_with_corner_angle(shape: &Shape, width: u32, height: u32, corner_angle: f32) -> Self {
+        let edges = color_edges(shape, corner_angle as f64);
+        let orientation = shape.inside_orientation();
+
+        let mut distance_field = MultiChannelDistanceField {
+            width, height,
+            distances: D::new(width as usize * height as usize * 3),
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let point = Vector::new(x as f64 + 0.5, y as f64 + 0.5);
+                let mut nearest: [Option<(&Segment, SegmentDistance)>; 3] = [None; 3];
+
+                for (segment, color) in &edges {
+                    let distance = segment.nearest(point);
+
+                    for (channel, nearest) in nearest.iter_mut().enumerate() {
+                        let is_nearer = nearest.as_ref()
+                            .map_or(true, |(_, nearest)| distance.is_nearer_than(nearest));
+
+                        if color & (1 << channel) != 0 && is_nearer {
+                            *nearest = Some((segment, distance));
+                        }
+                    }
+                }
+
+                let index = distance_field.flatten_index(x, y);
+                for (channel, nearest) in nearest.iter().enumerate() {
+                    // the inside of the shape has negative distances
+                    let distance = nearest
+                        .map(|(segment, distance)| - orientation * segment.pseudo_distance(point, &distance))
+                        .unwrap_or(f64::INFINITY);
+
+                    distance_field.distances.set(index + channel, distance as f32);
+                }
+            }
+        }
+
+        distance_field.correct_clashes();
+        distance_field
+    }
+
+    /// Finds pixels which would produce artifacts when interpolated with their neighbours,
+    /// because two channels change their sign between them,
+    /// and replaces all channels of these pixels with their median.
+    fn correct_clashes(&mut self) {
+        let threshold = 1.001;
+        let mut clashes = Vec::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let own = self.get_distances(x, y);
+
+                let is_clashing = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)]
+                    .iter().any(|&(neighbour_x, neighbour_y)| {
+                        let neighbour_x = x as i64 + neighbour_x;
+                        let neighbour_y = y as i64 + neighbour_y;
+                        let is_valid = neighbour_x >= 0 && neighbour_y >= 0
+                            && neighbour_x < self.width as i64 && neighbour_y < self.height as i64;
+
+                        let neighbour_distance = ((neighbour_x - x as i64).pow(2) + (neighbour_y - y as i64).pow(2)) as f32;
+
+                        is_valid && is_clashing(
+                            own, self.get_distances(neighbour_x as u32, neighbour_y as u32),
+                            threshold * neighbour_distance.sqrt()
+                        )
+                    });
+
+                if is_clashing {
+                    clashes.push((x, y));
+                }
+            }
+        }
+
+        for (x, y) in clashes {
+            let median = self.get_distance(x, y);
+            let index = self.flatten_index(x, y);
+
+            for channel in 0..3 {
+                self.distances.set(index + channel, median);
+            }
+        }
+    }
+
+    /// Returns the three channel distances of the specified pixel.
+    #[inline]
+    pub fn get_distances(&self, x: u32, y: u32) -> [f32; 3] {
+        let index = self.flatten_index(x, y);
+        [self.distances.get(index), self.distances.get(index + 1), self.distances.get(index + 2)]
+    }
+
+    /// Returns the signed distance of the specified pixel to the shape,
+    /// being the median of the three channels.
+    #[inline]
+    pub fn get_distance(&self, x: u32, y: u32) -> f32 {
+        let [red, green, blue] = self.get_distances(x, y);
+        median(red, green, blue)
+    }
+
+    /// Convert x and y pixel coordinates to the index of the first channel
+    /// of that pixel in the row-major image vector.
+    #[inline]
+    pub fn flatten_index(&self, x: u32, y: u32) -> usize {
+        debug_assert!(x < self.width && y < self.height, "Invalid pixel target index");
+        (self.width as usize * y as usize + x as usize) * 3
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one.
+    /// All distances smaller than `min` and larger than `max` will be clamped.
+    /// Returns `None` if the shape did not contain any segments.
+    pub fn normalize_clamped_distances(self, min: f32, max: f32) -> Option<NormalizedMultiChannelDistanceField<D>> {
+        NormalizedMultiChannelDistanceField::normalize_clamped(self, min, max)
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one,
+    /// like `normalize_clamped_distances`, but reports why the distances could not be normalized.
+    pub fn try_normalize_clamped_distances(self, min: f32, max: f32) -> Result<NormalizedMultiChannelDistanceField<D>> {
+        NormalizedMultiChannelDistanceField::try_normalize_clamped(self, min, max)
+    }
+}
+
+impl<D> NormalizedMultiChannelDistanceField<D> where D: DistanceStorage {
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one.
+    /// All distances smaller than `min` and larger than `max` will be clamped.
+    /// Edges (formerly zero-distances) will be put at exactly the middle between `min` and `max`,
+    /// being `0.5` if `min == -max`.
+    /// Returns `None` if the shape did not contain any segments.
+    pub fn normalize_clamped(distance_field: MultiChannelDistanceField<D>, min: f32, max: f32) -> Option<Self> {
+        Self::try_normalize_clamped(distance_field, min, max).ok()
+    }
+
+    /// Scales all distances such that the `min` distances are zero and `max` distances are one,
+    /// like `normalize_clamped`.
+    /// Returns an error if the shape did not contain any segments,
+    /// or if `min` and `max` are equal.
+    pub fn try_normalize_clamped(distance_field: MultiChannelDistanceField<D>, min: f32, max: f32) -> Result<Self> {
+        if min == max {
+            return Err(Error::ZeroSizeRange);
+        }
+
+        let mut normalized = NormalizedMultiChannelDistanceField {
+            width: distance_field.width,
+            height: distance_field.height,
+            distances: distance_field.distances,
+            zero_distance: (0.0 - min) / (max - min),
+        };
+
+        for index in 0..normalized.width as usize * normalized.height as usize * 3 {
+            let distance = normalized.distances.get(index);
+            if distance.is_infinite() { return Err(Error::NoShapeFound); }
+
+            let clamped = distance.min(max).max(min);
+            normalized.distances.set(index, (clamped - min) / (max - min));
+        }
+
+        Ok(normalized)
+    }
+
+    /// Convert the normalized distances to an interleaved `u8` RGB image with the range fully utilized.
+    pub fn to_rgb_u8(&self) -> Vec<u8> {
+        (0..self.width as usize * self.height as usize * 3)
+            .map(|index| (self.distances.get(index).clamp(0.0, 1.0) * u8::MAX as f32) as u8)
+            .collect()
+    }
+
+    /// Convert the normalized distances to an interleaved `u16` RGB image with the range fully utilized.
+    pub fn to_rgb_u16(&self) -> Vec<u16> {
+        (0..self.width as usize * self.height as usize * 3)
+            .map(|index| (self.distances.get(index).clamp(0.0, 1.0) * u16::MAX as f32) as u16)
+            .collect()
+    }
+
+    /// Convert the normalized distances to an `u8` RGB piston image with the range fully utilized.
+    #[cfg(feature = "piston_image")]
+    pub fn to_rgb_u8_image(&self) -> image::RgbImage {
+        image::RgbImage::from_raw(self.width, self.height, self.to_rgb_u8())
+            .expect("incorrect vector length")
+    }
+}
+
+
+/// Returns the median of the three values.
+#[inline]
+pub fn median(a: f32, b: f32, c: f32) -> f32 {
+    a.min(b).max(a.max(b).min(c))
+}
+
+/// Returns whether interpolating between the two pixels
+/// would produce a wrong median, because two channels change significantly.
+/// Only the pixel further away from the edge is flagged.
+fn is_clashing(own: [f32; 3], neighbour: [f32; 3], threshold: f32) -> bool {
+    let mut channels = [0, 1, 2];
+    channels.sort_by(|&a, &b| {
+        let difference = |channel: usize| (neighbour[channel] - own[channel]).abs();
+        difference(b).total_cmp(&difference(a))
+    });
+
+    let [_, second, smallest] = channels;
+    let is_equalized = neighbour[0] == neighbour[1] && neighbour[0] == neighbour[2];
+
+    (neighbour[second] - own[second]).abs() >= threshold
+        && !is_equalized
+        && own[smallest].abs() >= neighbour[smallest].abs()
+}
+
+/// Assign channels to all segments of the shape,
+/// such that the segments at both sides of a sharp corner have different colors.
+/// Segments are split if a contour has too few segments to be colored.
+fn color_edges(shape: &Shape, corner_angle: f64) -> Vec<(Segment, EdgeColor)> {
+    let cross_threshold = corner_angle.sin();
+    let mut seed = 0;
+    let mut colored = Vec::new();
+
+    for contour in &shape.contours {
+        let mut segments = contour.segments.clone();
+        if segments.is_empty() { continue; }
+
+        let corners: Vec<usize> = (0..segments.len())
+            .filter(|&index| {
+                let previous = &segments[(index + segments.len() - 1) % segments.len()];
+                is_corner(previous.direction_at(1.0), segments[index].direction_at(0.0), cross_threshold)
+            })
+            .collect();
+
+        // a smooth contour can use all channels for all segments
+        if corners.is_empty() {
+            colored.extend(segments.iter().map(|&segment| (segment, WHITE)));
+        }
+
+        // a teardrop shape, with only a single corner, is split into three colors
+        else if corners.len() == 1 {
+            let mut start_color = WHITE;
+            switch_color(&mut start_color, &mut seed, 0);
+            let mut end_color = start_color;
+            switch_color(&mut end_color, &mut seed, 0);
+            let colors = [start_color, WHITE, end_color];
+
+            let corner = corners[0];
+            if segments.len() < 3 {
+                segments = segments.iter().flat_map(Segment::split_in_thirds).collect();
+            }
+
+            let count = segments.len();
+            let corner = corner * count / contour.segments.len();
+
+            for index in 0..count {
+                let segment = segments[(corner + index) % count];
+                colored.push((segment, colors[symmetrical_trichotomy(index, count)]));
+            }
+        }
+
+        // switch the color at every corner
+        else {
+            let mut color = WHITE;
+            switch_color(&mut color, &mut seed, 0);
+            let initial_color = color;
+
+            let mut spline = 0;
+            let start = corners[0];
+
+            for index in 0..segments.len() {
+                let index = (start + index) % segments.len();
+
+                if spline + 1 < corners.len() && corners[spline + 1] == index {
+                    spline += 1;
+
+                    // the last spline must not share any channel with the first spline
+                    let banned = if spline == corners.len() - 1 { initial_color } else { 0 };
+                    switch_color(&mut color, &mut seed, banned);
+                }
+
+                colored.push((segments[index], color));
+            }
+        }
+    }
+
+    colored
+}
+
+/// Returns whether the two directions meet at a sharp angle,
+/// which is when they turn by more than 90 degrees
+/// or the sine of the turn is larger than the threshold.
+fn is_corner(incoming: Vector, outgoing: Vector, cross_threshold: f64) -> bool {
+    let incoming = incoming.normalized();
+    let outgoing = outgoing.normalized();
+    incoming.dot(outgoing) <= 0.0 || incoming.cross(outgoing).abs() > cross_threshold
+}
+
+/// Choose the next color, never sharing a channel with the banned color if possible.
+fn switch_color(color: &mut EdgeColor, seed: &mut usize, banned: EdgeColor) {
+    let combined = *color & banned;
+
+    if combined == RED || combined == GREEN || combined == BLUE {
+        *color = combined ^ WHITE;
+    }
+
+    else if *color == 0 || *color == WHITE {
+        *color = [CYAN, MAGENTA, YELLOW][*seed % 3];
+        *seed /= 3;
+    }
+
+    else {
+        let shifted = *color << (1 + (*seed & 1));
+        *color = (shifted | shifted >> 3) & WHITE;
+        *seed >>= 1;
+    }
+}
+
+/// Divides the positions `0..count` into three consecutive groups `0`, `1` and `2`,
+/// with the first and last group being the same size.
+fn symmetrical_trichotomy(position: usize, count: usize) -> usize {
+    let position = 2.875 * position as f64 / (count - 1) as f64 - 1.4375;
+    (position + 1.5).floor().clamp(0.0, 2.0) as usize
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::multi_channel::*;
+
+    fn square() -> Shape {
+        Shape::new(vec![ Contour::polygon(&[ (8.0, 8.0), (24.0, 8.0), (24.0, 24.0), (8.0, 24.0) ]) ])
+    }
+
+    fn square_distance(x: f32, y: f32) -> f32 {
+        let x = (x - 16.0).abs() - 8.0;
+        let y = (y - 16.0).abs() - 8.0;
+        (x.max(0.0).powi(2) + y.max(0.0).powi(2)).sqrt() + x.max(y).min(0.0)
+    }
+
+    #[test]
+    pub fn median_of_channels(){
+        assert_eq!(median(1.0, 2.0, 3.0), 2.0);
+        assert_eq!(median(3.0, 1.0, 2.0), 2.0);
+        assert_eq!(median(2.0, 3.0, 1.0), 2.0);
+        assert_eq!(median(-1.0, -1.0, 5.0), -1.0);
+    }
+
+    #[test]
+    pub fn square_median_is_distance(){
+        let field = MultiChannelDistanceField::<F32DistanceStorage>::compute(&square(), 32, 32);
+
+        for y in 0..32 {
+            for x in 0..32 {
+                let expected = square_distance(x as f32 + 0.5, y as f32 + 0.5);
+                let reconstructed = field.get_distance(x, y);
+
+                assert_eq!(expected < 0.0, reconstructed < 0.0, "wrong sign at {}, {}", x, y);
+
+                // outside of corners, the median is the distance to the nearest tangent line
+                let is_beside_corner = !(8..24).contains(&x) && !(8..24).contains(&y);
+                if !is_beside_corner {
+                    assert!((expected - reconstructed).abs() < 0.001, "wrong distance at {}, {}", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn reversed_square_median_is_distance(){
+        let mut reversed = square();
+        reversed.contours[0] = Contour::polygon(&[ (8.0, 8.0), (8.0, 24.0), (24.0, 24.0), (24.0, 8.0) ]);
+
+        let field = MultiChannelDistanceField::<F32DistanceStorage>::compute(&reversed, 32, 32);
+        assert!(field.get_distance(16, 16) < -7.0);
+        assert!(field.get_distance(1, 16) > 6.0);
+    }
+
+    #[test]
+    pub fn square_corners_use_different_channels(){
+        let field = MultiChannelDistanceField::<F32DistanceStorage>::compute(&square(), 32, 32);
+
+        // near a corner, the channels disagree, which is what keeps the corner sharp
+        let [red, green, blue] = field.get_distances(26, 25);
+        assert!(red != green || green != blue);
+
+        // the median is the distance to the farther tangent line, describing a sharp corner
+        assert_eq!(field.get_distance(26, 25), 2.5);
+    }
+
+    #[test]
+    pub fn smooth_contour_is_white(){
+        let circle = Shape::new(vec![ Contour::new(vec![
+            Segment::Cubic((16.0, 4.0), (22.6, 4.0), (28.0, 9.4), (28.0, 16.0)),
+            Segment::Cubic((28.0, 16.0), (28.0, 22.6), (22.6, 28.0), (16.0, 28.0)),
+            Segment::Cubic((16.0, 28.0), (9.4, 28.0), (4.0, 22.6), (4.0, 16.0)),
+            Segment::Cubic((4.0, 16.0), (4.0, 9.4), (9.4, 4.0), (16.0, 4.0)),
+        ])]);
+
+        let field = MultiChannelDistanceField::<F32DistanceStorage>::compute(&circle, 32, 32);
+        for y in 0..32 {
+            for x in 0..32 {
+                let [red, green, blue] = field.get_distances(x, y);
+                assert_eq!(red, green);
+                assert_eq!(green, blue);
+
+                let expected = ((x as f32 + 0.5 - 16.0).powi(2) + (y as f32 + 0.5 - 16.0).powi(2)).sqrt() - 12.0;
+                assert!((red - expected).abs() < 0.1, "wrong distance at {}, {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    pub fn teardrop_is_split_into_three_colors(){
+        let teardrop = Shape::new(vec![ Contour::new(vec![
+            Segment::Cubic((4.0, 16.0), (12.0, 0.0), (28.0, 4.0), (28.0, 16.0)),
+            Segment::Cubic((28.0, 16.0), (28.0, 28.0), (12.0, 32.0), (4.0, 16.0)),
+        ])]);
+
+        let edges = color_edges(&teardrop, DEFAULT_CORNER_ANGLE as f64);
+        assert_eq!(edges.len(), 6);
+
+        let mut colors: Vec<EdgeColor> = edges.iter().map(|&(_, color)| color).collect();
+        colors.dedup();
+        assert_eq!(colors.len(), 3);
+
+        // the segments at the corner share only a single channel
+        assert_eq!((edges[0].1 & edges[5].1).count_ones(), 1);
+    }
+
+    #[test]
+    pub fn normalize_to_rgb(){
+        let field = MultiChannelDistanceField::<F16DistanceStorage>::compute(&square(), 32, 32);
+        let normalized = field.normalize_clamped_distances(-4.0, 4.0).unwrap();
+        let rgb = normalized.to_rgb_u8();
+
+        assert_eq!(rgb.len(), 32 * 32 * 3);
+        assert_eq!(&rgb[0..3], &[255, 255, 255]);
+
+        let center = (16 * 32 + 16) * 3;
+        assert_eq!(&rgb[center .. center + 3], &[0, 0, 0]);
+    }
+
+    #[test]
+    pub fn empty_shape_cannot_be_normalized(){
+        let field = MultiChannelDistanceField::<F32DistanceStorage>::compute(&Shape::default(), 8, 8);
+        assert!(field.normalize_clamped_distances(-4.0, 4.0).is_none());
+    }
+
+    #[test]
+    pub fn empty_shape_reports_error(){
+        assert_eq!(
+            MultiChannelDistanceField::<F32DistanceStorage>::try_compute(&Shape::default(), 8, 8).err(),
+            Some(Error::NoShapeFound)
+        );
+
+        assert_eq!(
+            MultiChannelDistanceField::<F32DistanceStorage>::try_compute(&Shape::default(), 0, 8).err(),
+            Some(Error::EmptyImage)
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use crate::distance_field::{ SignedDistanceField, DistanceStorage, TargetStorage };
+
+/// Selects how the direction of each pixel in a `NormalField` is determined.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GradientMethod {
+    /// Uses the direction between the pixel and its nearest edge from the vector field.
+    /// This is precise even at the medial axis of shapes,
+    /// but requires the distance field to store its targets.
+    /// Edge pixels, which are their own target, use finite differences instead.
+    Targets,
+
+    /// Uses the central differences of the distances of the neighbouring pixels.
+    /// Works with any target storage, but is smoothed across the medial axis of shapes.
+    FiniteDifferences,
+}
+
+/// Contains one normalized direction per pixel, produced by `NormalField::compute`.
+/// Each direction points towards increasing distances, which is away from the shape
+/// for pixels outside and towards the nearest edge for pixels inside the shape.
+/// The y axis points downwards, like the rows of the image.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NormalField {
+    pub width: u32,
+    pub height: u32,
+
+    /// A row-major image vector with one `(x, y)` direction of length one per pixel.
+    /// Pixels without any direction, for example in an image without shapes, contain `(0, 0)`.
+    pub normals: Vec<(f32, f32)>,
+}
+
+
+impl NormalField {
+    /// Computes the direction of the gradient of each pixel in the distance field.
+    /// Panics if the targets are used but have been discarded by the target storage.
+    pub fn compute<D, T>(distance_field: &SignedDistanceField<D, T>, method: GradientMethod) -> Self
+        where D: DistanceStorage, T: TargetStorage
+    {
+        let (width, height) = (distance_field.width, distance_field.height);
+        let mut normals = Vec::with_capacity(width as usize * height as usize);
+
+        for y in 0..height {
+            for x in 0..width {
+                let gradient = match method {
+                    GradientMethod::Targets => target_gradient(distance_field, x, y)
+                        .unwrap_or_else(|| finite_difference_gradient(distance_field, x, y)),
+
+                    GradientMethod::FiniteDifferences => finite_difference_gradient(distance_field, x, y),
+                };
+
+                normals.push(normalized(gradient));
+            }
+        }
+
+        NormalField { width, height, normals }
+    }
+
+    /// Returns the direction of the specified pixel.
+    #[inline]
+    pub fn get_normal(&self, x: u32, y: u32) -> (f32, f32) {
+        self.normals[self.width as usize * y as usize + x as usize]
+    }
+
+    /// Convert the directions to an interleaved `u8` image with two channels,
+    /// mapping each component from `[-1, 1]` to `[0, 255]`.
+    pub fn to_rg_u8(&self) -> Vec<u8> {
+        self.normals.iter()
+            .flat_map(|&(x, y)| [ encode_component(x), encode_component(y) ])
+            .collect()
+    }
+
+    /// Convert the directions to an interleaved `u8` RGB normal map,
+    /// mapping each component from `[-1, 1]` to `[0, 255]`.
+    /// The blue channel contains the component which completes the direction to a three dimensional unit vector,
+    /// which is zero for all pixels with a direction and one for pixels without a direction.
+    pub fn to_rgb_u8(&self) -> Vec<u8> {
+        self.normals.iter()
+            .flat_map(|&(x, y)| {
+                let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+                [ encode_component(x), encode_component(y), encode_component(z) ]
+            })
+            .collect()
+    }
+
+    /// Convert the directions to an `u8` RGB piston image, like `to_rg_u8`, with the blue channel set to zero.
+    #[cfg(feature = "piston_image")]
+    pub fn to_rg_u8_image(&self) -> image::RgbImage {
+        let bytes = self.to_rg_u8().chunks(2)
+            .flat_map(|rg| [ rg[0], rg[1], 0 ])
+            .collect();
+
+        image::RgbImage::from_raw(self.width, self.height, bytes)
+            .expect("incorrect vector length")
+    }
+
+    /// Convert the directions to an `u8` RGB piston normal map, like `to_rgb_u8`.
+    #[cfg(feature = "piston_image")]
+    pub fn to_rgb_u8_image(&self) -> image::RgbImage {
+        image::RgbImage::from_raw(self.width, self.height, self.to_rgb_u8())
+            .expect("incorrect vector length")
+    }
+}
+
+/// The vector from the nearest edge to the pixel, pointing towards increasing distances.
+/// Returns `None` for edge pixels and for pixels without any edge.
+#[inline]
+fn target_gradient<D, T>(distance_field: &SignedDistanceField<D, T>, x: u32, y: u32) -> Option<(f32, f32)>
+    where D: DistanceStorage, T: TargetStorage
+{
+    let distance = distance_field.get_distance(x, y);
+    if !distance.is_finite() { return None; }
+
+    let (target_x, target_y) = distance_field.get_distance_target(x, y);
+    if (target_x, target_y) == (x, y) { return None; }
+
+    let away_from_edge = (x as f32 - target_x as f32, y as f32 - target_y as f32);
+
+    if distance < 0.0 { Some((-away_from_edge.0, -away_from_edge.1)) }
+    else { Some(away_from_edge) }
+}
+
+/// The central difference of the neighbouring distances,
+/// or the one-sided difference at the borders of the image.
+/// Infinite distances are ignored.
+#[inline]
+fn finite_difference_gradient<D, T>(distance_field: &SignedDistanceField<D, T>, x: u32, y: u32) -> (f32, f32)
+    where D: DistanceStorage, T: TargetStorage
+{
+    let distance = |x: u32, y: u32| Some(distance_field.get_distance(x, y)).filter(|distance| distance.is_finite());
+
+    let difference = |previous: Option<f32>, center: Option<f32>, next: Option<f32>| {
+        match (previous, center, next) {
+            (Some(previous), _, Some(next)) => (next - previous) / 2.0,
+            (Some(previous), Some(center), None) => center - previous,
+            (None, Some(center), Some(next)) => next - center,
+            _ => 0.0,
+        }
+    };
+
+    let center = distance(x, y);
+
+    let left = if x > 0 { distance(x - 1, y) } else { None };
+    let right = if x + 1 < distance_field.width { distance(x + 1, y) } else { None };
+    let up = if y > 0 { distance(x, y - 1) } else { None };
+    let down = if y + 1 < distance_field.height { distance(x, y + 1) } else { None };
+
+    (difference(left, center, right), difference(up, center, down))
+}
+
+/// Scales the vector to a length of one, or returns zero if the vector is zero.
+#[inline]
+fn normalized((x, y): (f32, f32)) -> (f32, f32) {
+    let length = (x * x + y * y).sqrt();
+    if length > 0.0 { (x / length, y / length) } else { (0.0, 0.0) }
+}
+
+/// Maps a component from `[-1, 1]` to `[0, 255]`.
+#[inline]
+fn encode_component(value: f32) -> u8 {
+    ((value * 0.5 + 0.5).clamp(0.0, 1.0) * u8::MAX as f32).round() as u8
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn circle_field() -> SignedDistanceField<F32DistanceStorage> {
+        let image = binary_image::of_function(64, 64, |x, y| {
+            (x as f32 - 32.0).powi(2) + (y as f32 - 32.0).powi(2) < 16.0 * 16.0
+        });
+
+        SignedDistanceField::compute_exact(&image)
+    }
+
+    #[test]
+    pub fn normals_point_away_from_the_circle(){
+        let field = circle_field();
+
+        for &method in &[ GradientMethod::Targets, GradientMethod::FiniteDifferences ] {
+            let normals = NormalField::compute(&field, method);
+
+            // outside on the right, inside on the right, outside above
+            let (x, y) = normals.get_normal(60, 32);
+            assert!(x > 0.99 && y.abs() < 0.1, "{:?}", (x, y));
+
+            let (x, y) = normals.get_normal(40, 32);
+            assert!(x > 0.99 && y.abs() < 0.1, "{:?}", (x, y));
+
+            let (x, y) = normals.get_normal(32, 4);
+            assert!(y < -0.99 && x.abs() < 0.1, "{:?}", (x, y));
+
+            // only the center of the circle may be without direction
+            for &(x, y) in &normals.normals {
+                let length = (x * x + y * y).sqrt();
+                assert!((length - 1.0).abs() < 1e-4 || length == 0.0);
+            }
+
+            assert!(normals.normals.iter().filter(|&&normal| normal == (0.0, 0.0)).count() <= 1);
+        }
+    }
+
+    #[test]
+    pub fn empty_image_has_no_normals(){
+        let image = binary_image::of_function(8, 8, |_, _| false);
+        let field = SignedDistanceField::<F32DistanceStorage>::compute_exact(&image);
+        let normals = NormalField::compute(&field, GradientMethod::Targets);
+
+        assert!(normals.normals.iter().all(|&normal| normal == (0.0, 0.0)));
+        assert_eq!(&normals.to_rgb_u8()[0..3], &[128, 128, 255]);
+    }
+
+    #[test]
+    pub fn encode_normal_maps(){
+        let normals = NormalField { width: 2, height: 1, normals: vec![ (1.0, 0.0), (0.0, -1.0) ] };
+        assert_eq!(normals.to_rg_u8(), vec![ 255, 128, 128, 0 ]);
+        assert_eq!(normals.to_rgb_u8(), vec![ 255, 128, 128, 128, 0, 128 ]);
+    }
+}
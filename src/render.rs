@@ -0,0 +1,278 @@
+//! Rendering of anti-aliased shapes and effects from distance fields.
+//! Distances are sampled bilinearly at the pixel centers,
+//! so that the rendered image has the same size as the distance field.
+
+use crate::distance_field::{ SignedDistanceField, NormalizedDistanceField, DistanceStorage, TargetStorage };
+use crate::sampler::{ Sampler, Interpolation, Border, DistanceGrid };
+
+/// A straight, not premultiplied, RGBA color.
+pub type Color = [u8; 4];
+
+/// A layer of a rendered image. All sizes are measured in pixels of the distance field.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Effect {
+    /// Fills the inside of the shapes.
+    Fill { color: Color },
+
+    /// Draws a line of the specified width centered on the edges of the shapes.
+    Outline { color: Color, width: f32 },
+
+    /// Draws a halo around the shapes, which fades out quadratically within the radius.
+    Glow { color: Color, radius: f32 },
+
+    /// Draws the shapes moved by the offset, with edges blurred by the softness.
+    Shadow { color: Color, offset: (f32, f32), softness: f32 },
+}
+
+/// A grid of distances measured in pixels, which the `Renderer` can draw directly.
+/// Implemented by `SignedDistanceField`, but not by `NormalizedDistanceField`,
+/// whose distances in the range `[0, 1]` must be drawn with `Renderer::render_normalized` instead.
+pub trait PixelDistanceGrid: DistanceGrid {}
+
+/// Renders distance fields to RGBA images by drawing each effect on top of the previous ones.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Renderer {
+    /// The color of the image before any effect is drawn.
+    pub background: Color,
+
+    /// The effects in the order they are drawn, the first effect being at the bottom.
+    pub effects: Vec<Effect>,
+
+    /// The width of the transition at the edges, in pixels of the distance field.
+    /// Larger values produce softer edges. Defaults to one pixel.
+    pub smoothing: f32,
+}
+
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer { background: [0, 0, 0, 0], effects: Vec::new(), smoothing: 1.0 }
+    }
+}
+
+impl Renderer {
+    pub fn new(background: Color) -> Self {
+        Renderer { background, .. Renderer::default() }
+    }
+
+    /// Adds an effect on top of all previous effects.
+    pub fn with_effect(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// Sets the width of the transition at the edges, in pixels of the distance field.
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Renders the distances, measured in pixels, to an interleaved `u8` RGBA image.
+    /// Accepts a `SignedDistanceField` or any other grid of unnormalized distances.
+    pub fn render(&self, distances: &impl PixelDistanceGrid) -> Vec<u8> {
+        let sampler = Sampler::new(Interpolation::Bilinear, Border::Clamp);
+
+        let distance = |x: f32, y: f32| sampler.sample(distances, x, y);
+
+        let mut rgba = Vec::with_capacity(distances.width() as usize * distances.height() as usize * 4);
+
+        for y in 0..distances.height() {
+            for x in 0..distances.width() {
+                let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+                let center = distance(x, y);
+
+                let color = self.effects.iter().fold(to_linear_color(self.background), |below, effect| {
+                    let (color, coverage) = match *effect {
+                        Effect::Fill { color } => (color, self.coverage(center, self.smoothing)),
+                        Effect::Outline { color, width } => (color, self.coverage(center.abs() - width * 0.5, self.smoothing)),
+
+                        Effect::Glow { color, radius } => {
+                            let falloff = (1.0 - center.max(0.0) / radius.max(f32::EPSILON)).clamp(0.0, 1.0);
+                            (color, falloff * falloff)
+                        },
+
+                        Effect::Shadow { color, offset, softness } => {
+                            let shifted = distance(x - offset.0, y - offset.1);
+                            (color, self.coverage(shifted, softness.max(self.smoothing)))
+                        },
+                    };
+
+                    let mut above = to_linear_color(color);
+                    above[3] *= coverage;
+                    blend_over(above, below)
+                });
+
+                rgba.extend(color.iter().map(|&channel| (channel.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8));
+            }
+        }
+
+        rgba
+    }
+
+    /// Renders a normalized distance field to an interleaved `u8` RGBA image.
+    /// The distances are converted back to pixels using the range that was used for normalizing,
+    /// which is `former_min_distance` and `former_max_distance` for unclamped normalization.
+    pub fn render_normalized<D, T>(&self, distance_field: &NormalizedDistanceField<D, T>, min: f32, max: f32) -> Vec<u8>
+        where D: DistanceStorage, T: TargetStorage
+    {
+        self.render(&Denormalized { distance_field, min, max })
+    }
+
+    /// Renders the distances, measured in pixels, to an RGBA piston image.
+    #[cfg(feature = "piston_image")]
+    pub fn render_image(&self, distances: &impl PixelDistanceGrid) -> image::RgbaImage {
+        image::RgbaImage::from_raw(distances.width(), distances.height(), self.render(distances))
+            .expect("incorrect vector length")
+    }
+
+    /// The fraction of a pixel which is inside a shape with the specified distance,
+    /// where the transition between inside and outside has the specified width.
+    #[inline]
+    fn coverage(&self, distance: f32, smoothing: f32) -> f32 {
+        (0.5 - distance / smoothing.max(f32::EPSILON)).clamp(0.0, 1.0)
+    }
+}
+
+impl<D, T> PixelDistanceGrid for SignedDistanceField<D, T> where D: DistanceStorage, T: TargetStorage {}
+
+/// A normalized distance field which returns distances measured in pixels.
+struct Denormalized<'f, D: DistanceStorage, T: TargetStorage> {
+    distance_field: &'f NormalizedDistanceField<D, T>,
+    min: f32,
+    max: f32,
+}
+
+impl<D, T> DistanceGrid for Denormalized<'_, D, T> where D: DistanceStorage, T: TargetStorage {
+    fn width(&self) -> u32 { self.distance_field.width }
+
+    fn height(&self) -> u32 { self.distance_field.height }
+
+    #[inline]
+    fn distance(&self, x: u32, y: u32) -> f32 {
+        self.min + self.distance_field.distance(x, y) * (self.max - self.min)
+    }
+}
+
+impl<D, T> PixelDistanceGrid for Denormalized<'_, D, T> where D: DistanceStorage, T: TargetStorage {}
+
+/// Converts the color to floating point channels in the range `[0, 1]`.
+#[inline]
+fn to_linear_color(color: Color) -> [f32; 4] {
+    let [r, g, b, a] = color;
+    [ r as f32, g as f32, b as f32, a as f32 ].map(|channel| channel / u8::MAX as f32)
+}
+
+/// Composites a straight alpha color on top of another straight alpha color.
+#[inline]
+fn blend_over(above: [f32; 4], below: [f32; 4]) -> [f32; 4] {
+    let alpha = above[3] + below[3] * (1.0 - above[3]);
+    if alpha <= 0.0 { return [0.0; 4]; }
+
+    let channel = |index: usize| (above[index] * above[3] + below[index] * below[3] * (1.0 - above[3])) / alpha;
+    [ channel(0), channel(1), channel(2), alpha ]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    const RED: render::Color = [255, 0, 0, 255];
+    const WHITE: render::Color = [255, 255, 255, 255];
+
+    fn square_field() -> SignedDistanceField<F32DistanceStorage> {
+        let image = binary_image::of_function(40, 40, |x, y| (10..30).contains(&x) && (10..30).contains(&y));
+        SignedDistanceField::compute_exact(&image)
+    }
+
+    fn pixel(rgba: &[u8], x: usize, y: usize) -> [u8; 4] {
+        let index = (y * 40 + x) * 4;
+        [ rgba[index], rgba[index + 1], rgba[index + 2], rgba[index + 3] ]
+    }
+
+    #[test]
+    pub fn fill_is_opaque_inside_and_transparent_outside(){
+        let rgba = Renderer::default().with_effect(Effect::Fill { color: RED }).render(&square_field());
+
+        assert_eq!(rgba.len(), 40 * 40 * 4);
+        assert_eq!(pixel(&rgba, 20, 20), RED);
+        assert_eq!(pixel(&rgba, 2, 2)[3], 0);
+
+        // the edge pixels are partially covered
+        let edge = pixel(&rgba, 10, 20)[3];
+        assert!(edge > 0 && edge < 255);
+    }
+
+    #[test]
+    pub fn outline_covers_only_the_edges(){
+        let rgba = Renderer::new([0, 0, 0, 255])
+            .with_effect(Effect::Outline { color: WHITE, width: 3.0 })
+            .render(&square_field());
+
+        assert_eq!(pixel(&rgba, 20, 20), [0, 0, 0, 255]);
+        assert_eq!(pixel(&rgba, 2, 2), [0, 0, 0, 255]);
+        assert_eq!(pixel(&rgba, 10, 20), WHITE);
+    }
+
+    #[test]
+    pub fn glow_fades_out(){
+        let rgba = Renderer::default().with_effect(Effect::Glow { color: WHITE, radius: 8.0 }).render(&square_field());
+
+        let near = pixel(&rgba, 8, 20)[3];
+        let far = pixel(&rgba, 4, 20)[3];
+        assert!(near > far && far > 0);
+        assert_eq!(pixel(&rgba, 0, 20)[3], 0);
+    }
+
+    #[test]
+    pub fn shadow_is_offset_below_the_fill(){
+        let rgba = Renderer::default()
+            .with_effect(Effect::Shadow { color: [0, 0, 0, 255], offset: (5.0, 5.0), softness: 1.0 })
+            .with_effect(Effect::Fill { color: RED })
+            .render(&square_field());
+
+        assert_eq!(pixel(&rgba, 20, 20), RED);
+        assert_eq!(pixel(&rgba, 32, 32), [0, 0, 0, 255]);
+        assert_eq!(pixel(&rgba, 5, 5)[3], 0);
+    }
+
+    #[test]
+    pub fn quantized_fields_render_like_unquantized_fields(){
+        let image = binary_image::of_function(40, 40, |x, y| (x as f32 - 20.0).powi(2) + (y as f32 - 20.0).powi(2) < 15.0 * 15.0);
+
+        // the center and the corners are saturated
+        let quantized = SignedDistanceField::<U8DistanceStorage<8>>::compute_exact(&image);
+        assert_eq!(quantized.get_distance(20, 20), f32::NEG_INFINITY);
+
+        let renderer = Renderer::default()
+            .with_effect(Effect::Glow { color: WHITE, radius: 4.0 })
+            .with_effect(Effect::Fill { color: RED });
+
+        let rgba = renderer.render(&quantized);
+        assert_eq!(pixel(&rgba, 20, 20), RED);
+        assert_eq!(pixel(&rgba, 0, 0)[3], 0);
+
+        let expected = renderer.render(&SignedDistanceField::<F32DistanceStorage>::compute_exact(&image));
+        let largest_difference = expected.iter().zip(&rgba)
+            .map(|(&expected, &rendered)| (expected as i32 - rendered as i32).abs())
+            .max().unwrap();
+
+        assert!(largest_difference <= 2);
+    }
+
+    #[test]
+    pub fn normalized_fields_render_like_unnormalized_fields(){
+        let renderer = Renderer::default().with_effect(Effect::Outline { color: WHITE, width: 2.0 });
+        let field = square_field();
+        let expected = renderer.render(&field);
+
+        let normalized = field.normalize_clamped_distances(-20.0, 20.0).unwrap();
+        let rendered = renderer.render_normalized(&normalized, -20.0, 20.0);
+
+        let largest_difference = expected.iter().zip(&rendered)
+            .map(|(&expected, &rendered)| (expected as i32 - rendered as i32).abs())
+            .max().unwrap();
+
+        assert!(largest_difference <= 1);
+    }
+}
@@ -0,0 +1,353 @@
+//! Sampling of distance fields at fractional positions.
+//! Positions are measured in pixels, where the distance of pixel `(x, y)`
+//! is located at the pixel center `(x + 0.5, y + 0.5)`, like the points of `iso_contours`.
+
+use crate::distance_field::{ SignedDistanceField, NormalizedDistanceField, DistanceStorage, TargetStorage };
+
+/// A grid of distances which can be sampled at fractional positions.
+/// Implemented by `SignedDistanceField` and `NormalizedDistanceField`.
+pub trait DistanceGrid {
+    fn width(&self) -> u32;
+
+    fn height(&self) -> u32;
+
+    /// The distance stored for the specified pixel.
+    fn distance(&self, x: u32, y: u32) -> f32;
+}
+
+/// How the distances between the pixels are reconstructed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Interpolation {
+    /// Uses the distance of the nearest pixel.
+    /// The gradient is zero everywhere.
+    Nearest,
+
+    /// Interpolates linearly between the four nearest pixels.
+    Bilinear,
+
+    /// Interpolates with a Catmull-Rom spline through the sixteen nearest pixels,
+    /// producing a continuous gradient.
+    Bicubic,
+}
+
+/// Which distance is used for pixels outside of the field.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Border {
+    /// Uses the distance of the nearest pixel at the border.
+    Clamp,
+
+    /// Continues with the pixels of the opposite border, for tiling fields.
+    Repeat,
+
+    /// Continues with the pixels of the same border in reverse order.
+    Mirror,
+
+    /// Uses the specified distance.
+    Constant(f32),
+}
+
+/// Samples distance fields at fractional positions,
+/// with the configured interpolation and border handling.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Sampler {
+    pub interpolation: Interpolation,
+    pub border: Border,
+}
+
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Sampler { interpolation: Interpolation::Bilinear, border: Border::Clamp }
+    }
+}
+
+impl Sampler {
+    pub fn new(interpolation: Interpolation, border: Border) -> Self {
+        Sampler { interpolation, border }
+    }
+
+    /// The interpolated distance at the specified position.
+    pub fn sample(&self, grid: &impl DistanceGrid, x: f32, y: f32) -> f32 {
+        self.sample_with_gradient(grid, x, y).0
+    }
+
+    /// The derivative of the interpolated distance at the specified position, in x and y direction.
+    /// In an unnormalized distance field, the gradient points away from the nearest edge
+    /// and has a length of about one, except near the medial axis of shapes.
+    pub fn gradient(&self, grid: &impl DistanceGrid, x: f32, y: f32) -> (f32, f32) {
+        self.sample_with_gradient(grid, x, y).1
+    }
+
+    /// The interpolated distance and its gradient at the specified position.
+    pub fn sample_with_gradient(&self, grid: &impl DistanceGrid, x: f32, y: f32) -> (f32, (f32, f32)) {
+        match self.interpolation {
+            Interpolation::Nearest => {
+                let distance = self.pixel(grid, x.floor() as i64, y.floor() as i64);
+                (distance, (0.0, 0.0))
+            },
+
+            // interpolate in the grid of pixel centers
+            Interpolation::Bilinear => self.interpolate(grid, x - 0.5, y - 0.5, 0, 2, linear_weights),
+            Interpolation::Bicubic => self.interpolate(grid, x - 0.5, y - 0.5, -1, 4, cubic_weights),
+        }
+    }
+
+    /// Computes the weighted sum of the pixels around the position, and its derivative.
+    /// The weights function returns the weights and their derivatives
+    /// for the fractional part of the position, starting at the pixel with the specified offset.
+    ///
+    /// Infinite distances, as found in empty fields, in saturated pixels of quantized storages,
+    /// or at constant infinite borders, would turn the weighted sum into `NaN`.
+    /// Therefore, the distance is infinite if all pixels with a weight are infinite with the same sign,
+    /// and otherwise all infinite pixels are clamped to the range of the finite pixels around the position.
+    #[inline]
+    fn interpolate(
+        &self, grid: &impl DistanceGrid, x: f32, y: f32, offset: i64, count: usize,
+        weights: fn(f32) -> ([f32; 4], [f32; 4])
+    ) -> (f32, (f32, f32)) {
+        let (floor_x, floor_y) = (x.floor(), y.floor());
+        let (weights_x, derivatives_x) = weights(x - floor_x);
+        let (weights_y, derivatives_y) = weights(y - floor_y);
+
+        let mut pixels = [[0.0; 4]; 4];
+        let (mut finite_min, mut finite_max) = (f32::INFINITY, f32::NEG_INFINITY);
+        let mut weighted_infinity = None;
+        let mut is_any_weighted_finite = false;
+        let mut heaviest = (0.0, 0.0);
+
+        for row in 0..count {
+            let pixel_y = floor_y as i64 + offset + row as i64;
+
+            for column in 0..count {
+                let pixel_x = floor_x as i64 + offset + column as i64;
+                let pixel = self.pixel(grid, pixel_x, pixel_y);
+                pixels[row][column] = pixel;
+
+                if pixel.is_finite() {
+                    finite_min = finite_min.min(pixel);
+                    finite_max = finite_max.max(pixel);
+                }
+
+                let weight = weights_x[column] * weights_y[row];
+                if weight == 0.0 { continue; }
+
+                if weight.abs() > heaviest.0 { heaviest = (weight.abs(), pixel); }
+
+                if pixel.is_finite() { is_any_weighted_finite = true; }
+                else if weighted_infinity.map_or(true, |infinity| infinity == pixel) { weighted_infinity = Some(pixel); }
+                else { is_any_weighted_finite = true; } // infinities with both signs cannot be interpolated
+            }
+        }
+
+        if !is_any_weighted_finite {
+            if let Some(infinity) = weighted_infinity { return (infinity, (0.0, 0.0)); }
+        }
+
+        // without any finite pixel, there is no range to clamp to
+        if finite_min > finite_max { return (heaviest.1, (0.0, 0.0)); }
+
+        let mut distance = 0.0;
+        let mut gradient = (0.0, 0.0);
+
+        for (row, pixels) in pixels.iter().enumerate().take(count) {
+            for (column, &pixel) in pixels.iter().enumerate().take(count) {
+                let pixel = pixel.clamp(finite_min, finite_max);
+
+                distance += pixel * weights_x[column] * weights_y[row];
+                gradient.0 += pixel * derivatives_x[column] * weights_y[row];
+                gradient.1 += pixel * weights_x[column] * derivatives_y[row];
+            }
+        }
+
+        (distance, gradient)
+    }
+
+    /// The distance of the specified pixel, which may be outside of the grid.
+    #[inline]
+    fn pixel(&self, grid: &impl DistanceGrid, x: i64, y: i64) -> f32 {
+        match (self.border_coordinate(x, grid.width()), self.border_coordinate(y, grid.height())) {
+            (Some(x), Some(y)) => grid.distance(x, y),
+            _ => match self.border { Border::Constant(distance) => distance, _ => f32::INFINITY },
+        }
+    }
+
+    /// Moves the coordinate into the range `[0, size)` as specified by the border,
+    /// or returns `None` if the constant border distance should be used.
+    #[inline]
+    fn border_coordinate(&self, coordinate: i64, size: u32) -> Option<u32> {
+        let size = size as i64;
+        if size == 0 { return None; }
+        if coordinate >= 0 && coordinate < size { return Some(coordinate as u32); }
+
+        let coordinate = match self.border {
+            Border::Clamp => coordinate.clamp(0, size - 1),
+            Border::Repeat => coordinate.rem_euclid(size),
+            Border::Mirror => {
+                let period = coordinate.rem_euclid(2 * size);
+                if period < size { period } else { 2 * size - 1 - period }
+            },
+            Border::Constant(_) => return None,
+        };
+
+        Some(coordinate as u32)
+    }
+}
+
+/// The weights of the two pixels around the position and their derivatives.
+#[inline]
+fn linear_weights(t: f32) -> ([f32; 4], [f32; 4]) {
+    ([ 1.0 - t, t, 0.0, 0.0 ], [ -1.0, 1.0, 0.0, 0.0 ])
+}
+
+/// The Catmull-Rom weights of the four pixels around the position and their derivatives.
+#[inline]
+fn cubic_weights(t: f32) -> ([f32; 4], [f32; 4]) {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    (
+        [
+            0.5 * (-t3 + 2.0 * t2 - t),
+            0.5 * (3.0 * t3 - 5.0 * t2 + 2.0),
+            0.5 * (-3.0 * t3 + 4.0 * t2 + t),
+            0.5 * (t3 - t2),
+        ],
+        [
+            0.5 * (-3.0 * t2 + 4.0 * t - 1.0),
+            0.5 * (9.0 * t2 - 10.0 * t),
+            0.5 * (-9.0 * t2 + 8.0 * t + 1.0),
+            0.5 * (3.0 * t2 - 2.0 * t),
+        ],
+    )
+}
+
+
+impl<D, T> DistanceGrid for SignedDistanceField<D, T> where D: DistanceStorage, T: TargetStorage {
+    fn width(&self) -> u32 { self.width }
+
+    fn height(&self) -> u32 { self.height }
+
+    #[inline]
+    fn distance(&self, x: u32, y: u32) -> f32 {
+        self.get_distance(x, y)
+    }
+}
+
+impl<D, T> DistanceGrid for NormalizedDistanceField<D, T> where D: DistanceStorage, T: TargetStorage {
+    fn width(&self) -> u32 { self.width }
+
+    fn height(&self) -> u32 { self.height }
+
+    #[inline]
+    fn distance(&self, x: u32, y: u32) -> f32 {
+        self.distances.get(self.width as usize * y as usize + x as usize)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn circle_field() -> SignedDistanceField<F32DistanceStorage> {
+        let image = binary_image::of_function(64, 64, |x, y| {
+            (x as f32 - 32.0).powi(2) + (y as f32 - 32.0).powi(2) < 20.0 * 20.0
+        });
+
+        SignedDistanceField::compute_exact(&image)
+    }
+
+    #[test]
+    pub fn pixel_centers_are_exact(){
+        let field = circle_field();
+
+        for &interpolation in &[ Interpolation::Nearest, Interpolation::Bilinear, Interpolation::Bicubic ] {
+            let sampler = Sampler::new(interpolation, Border::Clamp);
+
+            for &(x, y) in &[ (0, 0), (10, 32), (32, 32), (63, 40) ] {
+                let sampled = sampler.sample(&field, x as f32 + 0.5, y as f32 + 0.5);
+                assert!((sampled - field.get_distance(x, y)).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    pub fn bilinear_interpolates_between_pixels(){
+        let field = circle_field();
+        let sampler = Sampler::default();
+
+        let left = field.get_distance(5, 32);
+        let right = field.get_distance(6, 32);
+        assert!((sampler.sample(&field, 6.0, 32.5) - (left + right) / 2.0).abs() < 1e-5);
+
+        let (gradient_x, gradient_y) = sampler.gradient(&field, 6.0, 32.5);
+        assert!((gradient_x - (right - left)).abs() < 1e-5);
+        assert!(gradient_y.abs() < 1e-5);
+    }
+
+    #[test]
+    pub fn gradient_points_away_from_the_shape(){
+        let field = circle_field();
+        let sampler = Sampler::new(Interpolation::Bicubic, Border::Clamp);
+
+        let (gradient_x, gradient_y) = sampler.gradient(&field, 58.3, 32.5);
+        assert!(gradient_x > 0.8 && gradient_y.abs() < 0.2);
+
+        let (gradient_x, gradient_y) = sampler.gradient(&field, 32.5, 5.7);
+        assert!(gradient_y < -0.8 && gradient_x.abs() < 0.2);
+    }
+
+    #[test]
+    pub fn borders(){
+        let field = circle_field();
+        let sample = |border: Border, x: f32| Sampler::new(Interpolation::Nearest, border).sample(&field, x, 32.5);
+
+        assert_eq!(sample(Border::Clamp, -10.5), field.get_distance(0, 32));
+        assert_eq!(sample(Border::Repeat, -10.5), field.get_distance(53, 32));
+        assert_eq!(sample(Border::Mirror, -10.5), field.get_distance(10, 32));
+        assert_eq!(sample(Border::Constant(42.0), -10.5), 42.0);
+        assert_eq!(sample(Border::Mirror, 64.5), field.get_distance(63, 32));
+    }
+
+    #[test]
+    pub fn quantized_fields_can_be_sampled(){
+        let image = binary_image::of_function(64, 64, |x, y| {
+            (x as f32 - 32.0).powi(2) + (y as f32 - 32.0).powi(2) < 20.0 * 20.0
+        });
+
+        // distances further than 8 pixels from the edge are saturated to infinity
+        let field = SignedDistanceField::<U8DistanceStorage<8>>::compute_exact(&image);
+        assert_eq!(field.get_distance(32, 32), f32::NEG_INFINITY);
+
+        for &interpolation in &[ Interpolation::Bilinear, Interpolation::Bicubic ] {
+            for &border in &[ Border::Clamp, Border::Constant(f32::INFINITY) ] {
+                let sampler = Sampler::new(interpolation, border);
+
+                // saturated pixels stay saturated
+                assert_eq!(sampler.sample(&field, 32.5, 32.5), f32::NEG_INFINITY);
+                assert_eq!(sampler.sample(&field, 32.0, 31.7), f32::NEG_INFINITY);
+                assert_eq!(sampler.sample(&field, 0.5, 0.5), f32::INFINITY);
+
+                // pixels next to saturated pixels are clamped instead of becoming infinite
+                for &(x, y) in &[ (20.5, 32.5), (20.0, 32.0), (24.3, 31.1), (45.9, 40.2), (0.0, 0.0), (64.0, 32.0) ] {
+                    let (distance, (gradient_x, gradient_y)) = sampler.sample_with_gradient(&field, x, y);
+                    assert!(!distance.is_nan() && gradient_x.is_finite() && gradient_y.is_finite(), "NaN at {}, {}", x, y);
+                }
+
+                let near_edge = sampler.sample(&field, 12.5, 32.5);
+                assert!((near_edge - field.get_distance(12, 32)).abs() < 1e-4);
+            }
+        }
+
+        let empty = SignedDistanceField::<F32DistanceStorage>::compute_exact(&binary_image::of_function(8, 8, |_, _| false));
+        assert_eq!(Sampler::default().sample(&empty, 3.2, 4.7), f32::INFINITY);
+        assert_eq!(Sampler::new(Interpolation::Bicubic, Border::Clamp).sample(&empty, 3.2, 4.7), f32::INFINITY);
+    }
+
+    #[test]
+    pub fn normalized_fields_can_be_sampled(){
+        let normalized = circle_field().normalize_clamped_distances(-10.0, 10.0).unwrap();
+        let sampled = Sampler::default().sample(&normalized, 32.5, 32.5);
+        assert!((sampled - normalized.distances[32 * 64 + 32]).abs() < 1e-6);
+    }
+}
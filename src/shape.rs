@@ -0,0 +1,624 @@
+use std::ops::{ Add, Sub, Mul };
+
+
+/// A two-dimensional point, measured in pixels.
+pub type Point = (f32, f32);
+
+/// A single edge of a contour,
+/// being either a straight line, a quadratic Bézier curve or a cubic Bézier curve.
+/// Each variant contains all of its control points, including start and end.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Segment {
+    Line(Point, Point),
+    Quadratic(Point, Point, Point),
+    Cubic(Point, Point, Point, Point),
+}
+
+/// A closed outline, consisting of segments
+/// which each start where the previous segment ended.
+/// The last segment should end where the first segment starts.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Contour {
+    pub segments: Vec<Segment>,
+}
+
+/// A vector shape, consisting of any number of closed contours.
+/// Coordinates are measured in pixels of the distance field,
+/// such that the center of the pixel `(x, y)` is at `(x + 0.5, y + 0.5)`.
+/// Shapes can be constructed from contours or using a `PathBuilder`.
+///
+/// For multi-channel distance fields, holes must be oriented
+/// in the opposite direction of the contours which enclose them.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Shape {
+    pub contours: Vec<Contour>,
+}
+
+/// Decides which areas enclosed by the contours of a shape are inside the shape.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FillRule {
+    /// A point is inside the shape if the contours wind around it any number of times
+    /// other than zero, counting clockwise windings positive and counter-clockwise negative.
+    NonZero,
+
+    /// A point is inside the shape if the contours wind around it an odd number of times,
+    /// regardless of their direction.
+    EvenOdd,
+}
+
+/// Constructs a shape from path commands, similar to SVG paths.
+/// Each `move_to` starts a new contour, and all contours are closed automatically.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PathBuilder {
+    contours: Vec<Contour>,
+    segments: Vec<Segment>,
+    start: Point,
+    position: Point,
+}
+
+
+impl Shape {
+    /// Create a shape from the specified contours.
+    pub fn new(contours: Vec<Contour>) -> Self {
+        Shape { contours }
+    }
+
+    /// Returns `1.0` if the inside of the shape is on the right-hand side of its segments
+    /// (which means clockwise contours on screen, with the y-axis pointing down),
+    /// and `-1.0` otherwise. Derived from the total area of all contours,
+    /// such that holes do not affect the result.
+    pub(crate) fn inside_orientation(&self) -> f64 {
+        let area: f64 = self.contours.iter().map(Contour::signed_area).sum();
+        if area < 0.0 { -1.0 } else { 1.0 }
+    }
+}
+
+impl PathBuilder {
+    /// Create a path without any contours.
+    pub fn new() -> Self {
+        PathBuilder::default()
+    }
+
+    /// Close the current contour and start a new contour at the specified point.
+    pub fn move_to(&mut self, point: Point) -> &mut Self {
+        self.close();
+        self.start = point;
+        self.position = point;
+        self
+    }
+
+    /// Add a straight line from the current position to the specified point.
+    pub fn line_to(&mut self, point: Point) -> &mut Self {
+        self.segments.push(Segment::Line(self.position, point));
+        self.position = point;
+        self
+    }
+
+    /// Add a quadratic Bézier curve from the current position to the specified point.
+    pub fn quadratic_to(&mut self, control: Point, point: Point) -> &mut Self {
+        self.segments.push(Segment::Quadratic(self.position, control, point));
+        self.position = point;
+        self
+    }
+
+    /// Add a cubic Bézier curve from the current position to the specified point.
+    pub fn cubic_to(&mut self, first_control: Point, second_control: Point, point: Point) -> &mut Self {
+        self.segments.push(Segment::Cubic(self.position, first_control, second_control, point));
+        self.position = point;
+        self
+    }
+
+    /// Close the current contour with a straight line back to its start, if necessary.
+    /// The next segment will start at the start of the closed contour.
+    pub fn close(&mut self) -> &mut Self {
+        if !self.segments.is_empty() {
+            if self.position != self.start {
+                self.segments.push(Segment::Line(self.position, self.start));
+            }
+
+            let segments = std::mem::take(&mut self.segments);
+            self.contours.push(Contour { segments });
+        }
+
+        self.position = self.start;
+        self
+    }
+
+    /// Close the current contour and return all contours as a shape.
+    pub fn build(&mut self) -> Shape {
+        self.close();
+        Shape { contours: std::mem::take(&mut self.contours) }
+    }
+}
+
+impl Contour {
+    /// Create a contour from the specified segments.
+    pub fn new(segments: Vec<Segment>) -> Self {
+        Contour { segments }
+    }
+
+    /// Create a closed polygon with straight lines between the specified points.
+    pub fn polygon(points: &[Point]) -> Self {
+        let segments = (0..points.len())
+            .map(|index| Segment::Line(points[index], points[(index + 1) % points.len()]))
+            .collect();
+
+        Contour { segments }
+    }
+
+    /// The area enclosed by the control polygon of this contour,
+    /// positive if the contour is clockwise on screen.
+    fn signed_area(&self) -> f64 {
+        let mut area = 0.0;
+
+        for segment in &self.segments {
+            for line in segment.control_points().windows(2) {
+                area += line[0].cross(line[1]);
+            }
+        }
+
+        area * 0.5
+    }
+}
+
+impl Segment {
+
+    /// The first control point of this segment.
+    pub fn start(&self) -> Point {
+        match *self {
+            Segment::Line(start, _) => start,
+            Segment::Quadratic(start, _, _) => start,
+            Segment::Cubic(start, _, _, _) => start,
+        }
+    }
+
+    /// The last control point of this segment.
+    pub fn end(&self) -> Point {
+        match *self {
+            Segment::Line(_, end) => end,
+            Segment::Quadratic(_, _, end) => end,
+            Segment::Cubic(_, _, _, end) => end,
+        }
+    }
+
+    /// The point on this segment at the specified curve parameter in the range `[0, 1]`.
+    pub fn point(&self, t: f32) -> Point {
+        self.point_at(t as f64).to_point()
+    }
+
+    /// All control points as vectors, including start and end.
+    pub(crate) fn control_points(&self) -> Vec<Vector> {
+        match *self {
+            Segment::Line(a, b) => vec![a.into(), b.into()],
+            Segment::Quadratic(a, b, c) => vec![a.into(), b.into(), c.into()],
+            Segment::Cubic(a, b, c, d) => vec![a.into(), b.into(), c.into(), d.into()],
+        }
+    }
+
+    pub(crate) fn point_at(&self, t: f64) -> Vector {
+        let s = 1.0 - t;
+        match *self {
+            Segment::Line(a, b) => Vector::from(a) * s + Vector::from(b) * t,
+            Segment::Quadratic(a, b, c) =>
+                Vector::from(a) * (s * s) + Vector::from(b) * (2.0 * s * t) + Vector::from(c) * (t * t),
+            Segment::Cubic(a, b, c, d) =>
+                Vector::from(a) * (s * s * s) + Vector::from(b) * (3.0 * s * s * t)
+                    + Vector::from(c) * (3.0 * s * t * t) + Vector::from(d) * (t * t * t),
+        }
+    }
+
+    /// The first derivative of the curve at the specified parameter.
+    pub(crate) fn derivative_at(&self, t: f64) -> Vector {
+        let s = 1.0 - t;
+        match *self {
+            Segment::Line(a, b) => Vector::from(b) - a.into(),
+            Segment::Quadratic(a, b, c) =>
+                (Vector::from(b) - a.into()) * (2.0 * s) + (Vector::from(c) - b.into()) * (2.0 * t),
+            Segment::Cubic(a, b, c, d) =>
+                (Vector::from(b) - a.into()) * (3.0 * s * s) + (Vector::from(c) - b.into()) * (6.0 * s * t)
+                    + (Vector::from(d) - c.into()) * (3.0 * t * t),
+        }
+    }
+
+    /// The direction of the curve at the specified parameter.
+    /// Falls back to the overall direction of the segment where the derivative is zero.
+    pub(crate) fn direction_at(&self, t: f64) -> Vector {
+        let derivative = self.derivative_at(t);
+        if derivative.length() > 1e-12 { derivative }
+        else { Vector::from(self.end()) - self.start().into() }
+    }
+
+    /// Split this segment into three segments of the same kind
+    /// at the curve parameters one third and two thirds.
+    pub(crate) fn split_in_thirds(&self) -> [Segment; 3] {
+        let (first, rest) = self.split_at(1.0 / 3.0);
+        let (second, third) = rest.split_at(0.5);
+        [first, second, third]
+    }
+
+    /// Split this segment into two segments at the specified curve parameter,
+    /// using the algorithm of de Casteljau.
+    fn split_at(&self, t: f64) -> (Segment, Segment) {
+        let lerp = |a: Vector, b: Vector| a * (1.0 - t) + b * t;
+
+        match *self {
+            Segment::Line(a, b) => {
+                let middle = lerp(a.into(), b.into()).to_point();
+                (Segment::Line(a, middle), Segment::Line(middle, b))
+            },
+
+            Segment::Quadratic(a, b, c) => {
+                let ab = lerp(a.into(), b.into());
+                let bc = lerp(b.into(), c.into());
+                let middle = lerp(ab, bc).to_point();
+                (Segment::Quadratic(a, ab.to_point(), middle), Segment::Quadratic(middle, bc.to_point(), c))
+            },
+
+            Segment::Cubic(a, b, c, d) => {
+                let ab = lerp(a.into(), b.into());
+                let bc = lerp(b.into(), c.into());
+                let cd = lerp(c.into(), d.into());
+                let abc = lerp(ab, bc);
+                let bcd = lerp(bc, cd);
+                let middle = lerp(abc, bcd).to_point();
+
+                (
+                    Segment::Cubic(a, ab.to_point(), abc.to_point(), middle),
+                    Segment::Cubic(middle, bcd.to_point(), cd.to_point(), d)
+                )
+            },
+        }
+    }
+
+    /// Find the point on this segment which is nearest to the specified point.
+    pub(crate) fn nearest(&self, point: Vector) -> SegmentDistance {
+        let parameter = match *self {
+            Segment::Line(a, b) => {
+                let a = Vector::from(a);
+                let line = Vector::from(b) - a;
+                let squared_length = line.dot(line);
+
+                if squared_length == 0.0 { 0.0 }
+                else { ((point - a).dot(line) / squared_length).clamp(0.0, 1.0) }
+            },
+
+            Segment::Quadratic(..) | Segment::Cubic(..) => self.nearest_parameter(point),
+        };
+
+        let offset = point - self.point_at(parameter);
+        let direction = self.direction_at(parameter);
+
+        SegmentDistance {
+            distance: offset.length(),
+            parameter,
+            side: if direction.cross(offset) < 0.0 { -1.0 } else { 1.0 },
+            obliqueness: direction.normalized().dot(offset.normalized()).abs(),
+        }
+    }
+
+    /// Find the curve parameter of the nearest point by solving for all parameters
+    /// where the offset to the point is perpendicular to the curve.
+    /// These are the roots of the polynomial `(point_at(t) - point) · derivative_at(t)`,
+    /// which are compared to the start and end of the segment.
+    fn nearest_parameter(&self, point: Vector) -> f64 {
+        let squared_distance = |t: f64| {
+            let offset = self.point_at(t) - point;
+            offset.dot(offset)
+        };
+
+        // the curve in power basis, relative to the point
+        let mut curve = [Vector::default(); 4];
+        let degree = match *self {
+            Segment::Line(..) => unreachable!("lines are projected directly"),
+
+            Segment::Quadratic(a, b, c) => {
+                let (a, b, c) = (Vector::from(a), Vector::from(b), Vector::from(c));
+                curve[0] = a - point;
+                curve[1] = (b - a) * 2.0;
+                curve[2] = a - b * 2.0 + c;
+                2
+            },
+
+            Segment::Cubic(a, b, c, d) => {
+                let (a, b, c, d) = (Vector::from(a), Vector::from(b), Vector::from(c), Vector::from(d));
+                curve[0] = a - point;
+                curve[1] = (b - a) * 3.0;
+                curve[2] = (a - b * 2.0 + c) * 3.0;
+                curve[3] = d - a + (b - c) * 3.0;
+                3
+            },
+        };
+
+        let mut polynomial = [0.0; MAX_POLYNOMIAL_DEGREE + 1];
+        for (power, coefficient) in curve[..= degree].iter().enumerate() {
+            for derivative_power in 1 ..= degree {
+                let derivative_coefficient = curve[derivative_power] * derivative_power as f64;
+                polynomial[power + derivative_power - 1] += coefficient.dot(derivative_coefficient);
+            }
+        }
+
+        let mut roots = [0.0; MAX_POLYNOMIAL_DEGREE];
+        let root_count = unit_interval_roots(&polynomial[.. 2 * degree], &mut roots);
+
+        roots[.. root_count].iter().fold(
+            if squared_distance(0.0) <= squared_distance(1.0) { 0.0 } else { 1.0 },
+            |nearest, &t| if squared_distance(t) < squared_distance(nearest) { t } else { nearest }
+        )
+    }
+
+    /// The distance to the nearest point on this segment,
+    /// but for points beyond the start or the end of the segment,
+    /// measures the distance to the tangent line extending from there instead.
+    /// This keeps corners sharp when combining the distances of multiple segments.
+    pub(crate) fn pseudo_distance(&self, point: Vector, nearest: &SegmentDistance) -> f64 {
+        let endpoint = {
+            if nearest.parameter <= 0.0 { Some(0.0) }
+            else if nearest.parameter >= 1.0 { Some(1.0) }
+            else { None }
+        };
+
+        if let Some(t) = endpoint {
+            let direction = self.direction_at(t).normalized();
+            let offset = point - self.point_at(t);
+            let is_beyond = if t == 0.0 { offset.dot(direction) < 0.0 } else { offset.dot(direction) > 0.0 };
+
+            if is_beyond {
+                let perpendicular = direction.cross(offset);
+                if perpendicular.abs() <= nearest.distance {
+                    return perpendicular;
+                }
+            }
+        }
+
+        nearest.distance * nearest.side
+    }
+}
+
+/// The highest degree of the polynomials solved when searching the nearest point on a cubic curve.
+const MAX_POLYNOMIAL_DEGREE: usize = 5;
+
+/// Find the roots of the polynomial with the specified coefficients, lowest power first,
+/// inside the range `[0, 1]`. Returns the number of roots written to the start of the array, in ascending order.
+/// The roots of the derivative split the range into intervals where the polynomial is monotonic,
+/// such that each interval contains at most one root, which is found by bisection.
+fn unit_interval_roots(coefficients: &[f64], roots: &mut [f64; MAX_POLYNOMIAL_DEGREE]) -> usize {
+    let degree = coefficients.len().saturating_sub(1);
+    if degree == 0 { return 0; }
+
+    let mut derivative = [0.0; MAX_POLYNOMIAL_DEGREE];
+    for power in 1 ..= degree {
+        derivative[power - 1] = coefficients[power] * power as f64;
+    }
+
+    let mut extrema = [0.0; MAX_POLYNOMIAL_DEGREE];
+    let extremum_count = unit_interval_roots(&derivative[.. degree], &mut extrema);
+
+    let mut root_count = 0;
+    let mut start = 0.0;
+
+    for &end in extrema[.. extremum_count].iter().chain(std::iter::once(&1.0)) {
+        if let Some(root) = monotonic_root(coefficients, start, end) {
+            // a root at an extremum would otherwise be found in both adjacent intervals
+            if root_count == 0 || roots[root_count - 1] != root {
+                roots[root_count] = root;
+                root_count += 1;
+            }
+        }
+
+        start = end;
+    }
+
+    root_count
+}
+
+/// Find the root of the polynomial inside the specified range by bisection,
+/// assuming that the polynomial is monotonic inside the range.
+fn monotonic_root(coefficients: &[f64], mut start: f64, mut end: f64) -> Option<f64> {
+    let evaluate = |t: f64| coefficients.iter().rev().fold(0.0, |sum, &coefficient| sum * t + coefficient);
+
+    let start_value = evaluate(start);
+    let end_value = evaluate(end);
+
+    if start_value == 0.0 { return Some(start); }
+    if end_value == 0.0 { return Some(end); }
+    if (start_value < 0.0) == (end_value < 0.0) { return None; }
+
+    for _ in 0 .. 64 {
+        let middle = 0.5 * (start + end);
+        if middle <= start || middle >= end { break; }
+
+        let middle_value = evaluate(middle);
+        if middle_value == 0.0 { return Some(middle); }
+
+        if (middle_value < 0.0) == (start_value < 0.0) { start = middle; }
+        else { end = middle; }
+    }
+
+    Some(0.5 * (start + end))
+}
+
+/// A shape, prepared for searching the nearest outline point
+/// and for deciding which pixels are inside the shape.
+pub(crate) struct PreparedShape {
+    segments: Vec<Segment>,
+
+    /// The bounding box of the control points of each segment.
+    bounds: Vec<(Vector, Vector)>,
+
+    /// All segments, approximated by straight lines.
+    lines: Vec<(Vector, Vector)>,
+}
+
+impl PreparedShape {
+    pub fn new(shape: &Shape) -> Self {
+        let segments: Vec<Segment> = shape.contours.iter()
+            .flat_map(|contour| contour.segments.iter().cloned())
+            .collect();
+
+        let bounds = segments.iter().map(|segment| {
+            let points = segment.control_points();
+            let min = points.iter().fold(points[0], |min, point| Vector::new(min.x.min(point.x), min.y.min(point.y)));
+            let max = points.iter().fold(points[0], |max, point| Vector::new(max.x.max(point.x), max.y.max(point.y)));
+            (min, max)
+        }).collect();
+
+        let lines = segments.iter().flat_map(|segment| {
+            let points = segment.control_points();
+            let length: f64 = points.windows(2).map(|line| (line[1] - line[0]).length()).sum();
+
+            // lines need not be split, curves are split into pieces of about a quarter pixel
+            let pieces = match segment {
+                Segment::Line(..) => 1,
+                _ => ((length * 4.0).ceil() as usize).clamp(1, 4096),
+            };
+
+            (0..pieces).map(move |piece| (
+                segment.point_at(piece as f64 / pieces as f64),
+                segment.point_at((piece + 1) as f64 / pieces as f64)
+            ))
+        }).collect();
+
+        PreparedShape { segments, bounds, lines }
+    }
+
+    /// Find the point on the outline which is nearest to the specified point.
+    /// The index of the previously nearest segment can be specified as a hint,
+    /// which speeds up the search for neighbouring points.
+    /// Returns `None` if the shape has no segments.
+    pub fn nearest(&self, point: Vector, hint: &mut usize) -> Option<(Vector, f64)> {
+        let hinted = self.segments.get(*hint)?.nearest(point);
+        let mut nearest = (*hint, hinted);
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            let (min, max) = self.bounds[index];
+            let outside = Vector::new(
+                (min.x - point.x).max(point.x - max.x).max(0.0),
+                (min.y - point.y).max(point.y - max.y).max(0.0),
+            );
+
+            // the curve is within its control point bounds, so it cannot be nearer than these
+            if outside.length() >= nearest.1.distance {
+                continue;
+            }
+
+            let distance = segment.nearest(point);
+            if distance.distance < nearest.1.distance {
+                nearest = (index, distance);
+            }
+        }
+
+        *hint = nearest.0;
+        Some((self.segments[nearest.0].point_at(nearest.1.parameter), nearest.1.distance))
+    }
+
+    /// For each pixel center in the row at the specified height, decide if it is inside the shape.
+    pub fn fill_row(&self, y: f64, fill_rule: FillRule, inside: &mut [bool]) {
+        let mut crossings: Vec<(f64, i64)> = self.lines.iter()
+            .filter(|(start, end)| (start.y <= y) != (end.y <= y))
+            .map(|&(start, end)| (
+                start.x + (y - start.y) * (end.x - start.x) / (end.y - start.y),
+                if end.y > start.y { 1 } else { -1 }
+            ))
+            .collect();
+
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut crossings = crossings.into_iter().peekable();
+        let mut winding = 0;
+
+        for (x, inside) in inside.iter_mut().enumerate() {
+            while let Some((_, direction)) = crossings.next_if(|&(crossing, _)| crossing < x as f64 + 0.5) {
+                winding += direction;
+            }
+
+            *inside = match fill_rule {
+                FillRule::NonZero => winding != 0,
+                FillRule::EvenOdd => winding % 2 != 0,
+            };
+        }
+    }
+}
+
+/// The result of searching the nearest point on a segment.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct SegmentDistance {
+
+    /// The unsigned distance to the nearest point.
+    pub distance: f64,
+
+    /// The curve parameter of the nearest point.
+    pub parameter: f64,
+
+    /// Whether the point is on the right-hand side (`1.0`) or the left-hand side (`-1.0`)
+    /// of the segment, looking along the segment's direction with the y-axis pointing down.
+    pub side: f64,
+
+    /// How far the direction to the point deviates from being perpendicular to the segment.
+    /// Used to pick the correct segment at corners, where multiple segments are equally near.
+    pub obliqueness: f64,
+}
+
+impl SegmentDistance {
+    /// Returns true if this distance is nearer than the other distance,
+    /// preferring the more perpendicular segment if both are equally near.
+    pub fn is_nearer_than(&self, other: &SegmentDistance) -> bool {
+        self.distance < other.distance
+            || (self.distance == other.distance && self.obliqueness < other.obliqueness)
+    }
+}
+
+
+/// A two-dimensional vector with double precision, used for internal geometric computations.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub(crate) struct Vector {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Vector {
+    pub fn new(x: f64, y: f64) -> Self {
+        Vector { x, y }
+    }
+
+    pub fn dot(self, other: Vector) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn cross(self, other: Vector) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalized(self) -> Vector {
+        let length = self.length();
+        if length == 0.0 { self } else { self * (1.0 / length) }
+    }
+
+    pub fn to_point(self) -> Point {
+        (self.x as f32, self.y as f32)
+    }
+}
+
+impl From<Point> for Vector {
+    fn from((x, y): Point) -> Self {
+        Vector::new(x as f64, y as f64)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+    fn add(self, other: Vector) -> Vector { Vector::new(self.x + other.x, self.y + other.y) }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+    fn sub(self, other: Vector) -> Vector { Vector::new(self.x - other.x, self.y - other.y) }
+}
+
+impl Mul<f64> for Vector {
+    type Output = Vector;
+    fn mul(self, factor: f64) -> Vector { Vector::new(self.x * factor, self.y * factor) }
+}
@@ -0,0 +1,238 @@
+//! Discrete Voronoi diagrams of labelled regions.
+//! Every pixel is assigned the label of the region whose nearest pixel is closest,
+//! using the targets of the exact unsigned distance field.
+
+use crate::binary_image::BinaryImage;
+use crate::distance_field::{ SignedDistanceField, DistanceStorage, TargetStorage, U16TargetStorage, ScratchContext };
+use crate::error::{ Error, Result };
+
+/// An image which owns a row major vector with one label per pixel.
+/// A label of zero marks pixels which do not belong to any region.
+/// As a binary image, all pixels with a label other than zero are inside.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LabelImage {
+    pub width: u32,
+    pub height: u32,
+
+    /// A row-major image vector with one label per pixel.
+    pub labels: Vec<u32>,
+}
+
+/// Create a label image from a row major vector of labels, with zero being "no region".
+pub fn of_label_vec(labels: Vec<u32>, width: u32, height: u32) -> LabelImage {
+    assert_eq!(labels.len(), width as usize * height as usize, "Buffer dimension mismatch");
+    LabelImage { width, height, labels }
+}
+
+/// Create a label image from a row major vector of labels, with zero being "no region".
+/// Returns an error if the buffer length does not match the dimensions or if the image has no pixels.
+pub fn try_of_label_vec(labels: Vec<u32>, width: u32, height: u32) -> Result<LabelImage> {
+    let expected = width as usize * height as usize;
+    if labels.len() != expected { return Err(Error::DimensionMismatch { expected, actual: labels.len() }); }
+    if expected == 0 { return Err(Error::EmptyImage); }
+
+    Ok(LabelImage { width, height, labels })
+}
+
+/// Labels each connected region of inside pixels with a distinct number, starting at one,
+/// in the order in which the regions first appear in the rows of the image.
+/// Pixels are connected to their four direct neighbours, like the edges of a distance field.
+pub fn connected_components(image: &impl BinaryImage) -> LabelImage {
+    let (width, height) = (image.width(), image.height());
+    let mut labels = vec![ 0; width as usize * height as usize ];
+    let mut pending = Vec::new();
+    let mut next_label = 1;
+
+    for y in 0..height {
+        for x in 0..width {
+            if labels[width as usize * y as usize + x as usize] != 0 || !image.is_inside(x, y) {
+                continue;
+            }
+
+            labels[width as usize * y as usize + x as usize] = next_label;
+            pending.push((x, y));
+
+            while let Some((x, y)) = pending.pop() {
+                let neighbours = [
+                    (x.wrapping_sub(1), y), (x + 1, y),
+                    (x, y.wrapping_sub(1)), (x, y + 1),
+                ];
+
+                for (x, y) in neighbours {
+                    if x >= width || y >= height { continue; }
+
+                    let label = &mut labels[width as usize * y as usize + x as usize];
+                    if *label == 0 && image.is_inside(x, y) {
+                        *label = next_label;
+                        pending.push((x, y));
+                    }
+                }
+            }
+
+            next_label += 1;
+        }
+    }
+
+    LabelImage { width, height, labels }
+}
+
+impl LabelImage {
+    /// Returns the label of the specified pixel, which is zero outside of all regions.
+    #[inline]
+    pub fn get_label(&self, x: u32, y: u32) -> u32 {
+        self.labels[self.width as usize * y as usize + x as usize]
+    }
+}
+
+impl BinaryImage for LabelImage {
+    #[inline]
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    fn is_inside(&self, x: u32, y: u32) -> bool {
+        self.get_label(x, y) != 0
+    }
+}
+
+
+/// The territory of each labelled region, produced by `Territories::compute`.
+/// Contains the unsigned distance from every pixel to the nearest labelled pixel,
+/// and the label of the region which that nearest pixel belongs to.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Territories<D: DistanceStorage, T: TargetStorage = U16TargetStorage> {
+    /// The exact distance from every pixel to the center of the nearest labelled pixel,
+    /// like `SignedDistanceField::compute_unsigned` with `PixelSet::Inside`.
+    /// The distance targets are the nearest labelled pixels.
+    pub distance_field: SignedDistanceField<D, T>,
+
+    /// The label of the nearest region of every pixel.
+    /// Only zero if the image does not contain any region.
+    pub labels: LabelImage,
+}
+
+impl<D, T> Territories<D, T> where D: DistanceStorage, T: TargetStorage {
+    /// Computes the nearest region of every pixel in the label image.
+    /// Also works with a `DiscardedTargetStorage`, as the labels are assigned before the targets are discarded.
+    pub fn compute(labels: &LabelImage) -> Self {
+        let mut territories: Self = Territories {
+            distance_field: SignedDistanceField::new(0, 0),
+            labels: LabelImage { width: 0, height: 0, labels: Vec::new() },
+        };
+
+        territories.compute_into(labels, &mut ScratchContext::default());
+        territories.distance_field.distance_targets.release();
+        territories
+    }
+
+    /// Computes the nearest region of every pixel in the label image, like `compute`,
+    /// but reuses the storage of these territories and the buffers of the scratch context.
+    pub fn compute_into(&mut self, labels: &LabelImage, scratch: &mut ScratchContext) {
+        let (width, height) = (labels.width, labels.height);
+        let distance_field = &mut self.distance_field;
+
+        distance_field.compute_exact_features_into(width, height, |x, y| labels.get_label(x, y) != 0, scratch);
+
+        self.labels.width = width;
+        self.labels.height = height;
+        self.labels.labels.clear();
+        self.labels.labels.extend(
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| {
+                    if distance_field.get_distance(x, y).is_infinite() { return 0; }
+
+                    let (target_x, target_y) = distance_field.get_distance_target(x, y);
+                    labels.get_label(target_x, target_y)
+                })
+        );
+
+        distance_field.distance_targets.finish();
+    }
+
+    /// Returns the label of the nearest region of the specified pixel.
+    #[inline]
+    pub fn get_label(&self, x: u32, y: u32) -> u32 {
+        self.labels.get_label(x, y)
+    }
+
+    /// Returns the distance from the specified pixel to the nearest pixel of its region.
+    #[inline]
+    pub fn get_distance(&self, x: u32, y: u32) -> f32 {
+        self.distance_field.get_distance(x, y)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    pub fn connected_components_are_numbered_in_row_order(){
+        let image = binary_image::of_bool_vec(vec![
+            true,  true,  false, false, true,
+            false, false, false, true,  true,
+            false, true,  false, false, false,
+            true,  true,  false, true,  false,
+        ], 5, 4);
+
+        let labels = territory::connected_components(&image);
+        assert_eq!(labels.labels, vec![
+            1, 1, 0, 0, 2,
+            0, 0, 0, 2, 2,
+            0, 3, 0, 0, 0,
+            3, 3, 0, 4, 0,
+        ]);
+    }
+
+    #[test]
+    pub fn pixels_belong_to_the_nearest_region(){
+        let mut labels = vec![ 0; 30 * 20 ];
+        labels[5 * 30 + 5] = 7;
+        labels[14 * 30 + 24] = 9;
+        labels[15 * 30 + 24] = 9;
+
+        let labels = territory::of_label_vec(labels, 30, 20);
+        let territories = Territories::<F32DistanceStorage>::compute(&labels);
+
+        for y in 0..20 {
+            for x in 0..30 {
+                let distance_to = |region_x: f32, region_y: f32| {
+                    ((x as f32 - region_x).powi(2) + (y as f32 - region_y).powi(2)).sqrt()
+                };
+
+                let to_seven = distance_to(5.0, 5.0);
+                let to_nine = distance_to(24.0, 14.0).min(distance_to(24.0, 15.0));
+
+                assert!((territories.get_distance(x, y) - to_seven.min(to_nine)).abs() < 1e-4);
+
+                if to_seven < to_nine { assert_eq!(territories.get_label(x, y), 7); }
+                if to_nine < to_seven { assert_eq!(territories.get_label(x, y), 9); }
+            }
+        }
+    }
+
+    #[test]
+    pub fn territories_with_discarded_targets(){
+        let image = binary_image::of_function(16, 8, |x, y| y == 4 && (x == 1 || x == 14));
+        let labels = territory::connected_components(&image);
+        let territories = Territories::<F32DistanceStorage, DiscardedTargetStorage>::compute(&labels);
+
+        assert_eq!(territories.get_label(0, 0), 1);
+        assert_eq!(territories.get_label(7, 7), 1);
+        assert_eq!(territories.get_label(8, 7), 2);
+        assert_eq!(territories.get_label(15, 0), 2);
+
+        let empty = territory::of_label_vec(vec![ 0; 16 ], 4, 4);
+        let territories = Territories::<F32DistanceStorage>::compute(&empty);
+        assert!(territories.labels.labels.iter().all(|&label| label == 0));
+        assert!(territory::try_of_label_vec(vec![ 0; 15 ], 4, 4).is_err());
+    }
+}